@@ -0,0 +1,15 @@
+use std::env;
+use std::net::TcpListener;
+
+use library_structure::controller::{demo_directory, serve};
+
+fn main() -> std::io::Result<()> {
+    let addr = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:7878".to_string());
+
+    let listener = TcpListener::bind(&addr)?;
+    println!("Door controller listening on {}", addr);
+
+    serve(listener, demo_directory())
+}
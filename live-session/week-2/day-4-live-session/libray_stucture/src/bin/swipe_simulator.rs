@@ -0,0 +1,80 @@
+use std::env;
+use std::fs;
+use std::io::BufReader;
+use std::net::TcpStream;
+
+use library_structure::controller::{SwipeRequest, send_swipe};
+use library_structure::usage::Zone;
+
+// Used when no scenario file is given, matches the cards in demo_directory
+fn default_scenario() -> Vec<SwipeRequest> {
+    vec![
+        SwipeRequest {
+            card_id: 1,
+            zone: Zone::Garage,
+        },
+        SwipeRequest {
+            card_id: 1,
+            zone: Zone::ServerRoom,
+        },
+        SwipeRequest {
+            card_id: 2,
+            zone: Zone::Garage,
+        },
+        SwipeRequest {
+            card_id: 3,
+            zone: Zone::Garage,
+        },
+        SwipeRequest {
+            card_id: 4,
+            zone: Zone::ServerRoom,
+        },
+        SwipeRequest {
+            card_id: 5,
+            zone: Zone::Garage,
+        },
+        SwipeRequest {
+            card_id: 6,
+            zone: Zone::Kitchen,
+        },
+        SwipeRequest {
+            card_id: 99,
+            zone: Zone::Garage,
+        },
+    ]
+}
+
+fn load_scenario(path: &str) -> Result<Vec<SwipeRequest>, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(|e| e.to_string()))
+        .collect()
+}
+
+fn main() -> Result<(), String> {
+    let mut args = env::args().skip(1);
+    let addr = args.next().unwrap_or_else(|| "127.0.0.1:7878".to_string());
+
+    let scenario = match args.next() {
+        Some(path) => load_scenario(&path)?,
+        None => default_scenario(),
+    };
+
+    let stream = TcpStream::connect(&addr).map_err(|e| e.to_string())?;
+    let mut connection = BufReader::new(stream);
+
+    for swipe in &scenario {
+        let response = send_swipe(&mut connection, swipe)?;
+        match response.reason {
+            None => println!("card {} at {:?}: granted", swipe.card_id, swipe.zone),
+            Some(reason) => println!(
+                "card {} at {:?}: denied ({})",
+                swipe.card_id, swipe.zone, reason
+            ),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,219 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::directory::Directory;
+use crate::usage::*;
+
+// One line of JSON per swipe, e.g. {"card_id":1,"zone":"Garage"}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SwipeRequest {
+    pub card_id: u64,
+    pub zone: Zone,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SwipeResponse {
+    pub card_id: Option<u64>,
+    pub granted: bool,
+    pub reason: Option<String>,
+}
+
+// Same people every time the daemon starts, so the simulator knows which
+// card ids to swipe:
+// 1 Ada (Manager), 2 Tobi (SocialMedia), 3 Kemi (ITDepartment, lost),
+// 4 Kemi (ITDepartment), 5 Segun (MediaTeam, terminated), 6 Bisi (KitchenStaff)
+pub fn demo_directory() -> Directory {
+    let mut directory = Directory::new();
+
+    let ada = directory.add_employee("Ada", EmployeeType::Manager);
+    let tobi = directory.add_employee("Tobi", EmployeeType::SocialMedia);
+    let kemi = directory.add_employee("Kemi", EmployeeType::ITDepartment);
+    let segun = directory.add_employee("Segun", EmployeeType::MediaTeam);
+    let bisi = directory.add_employee("Bisi", EmployeeType::KitchenStaff);
+
+    directory.issue_card(ada).unwrap();
+    directory.issue_card(tobi).unwrap();
+    let lost = directory.issue_card(kemi).unwrap();
+    directory.issue_card(kemi).unwrap();
+    directory.issue_card(segun).unwrap();
+    directory.issue_card(bisi).unwrap();
+
+    directory.report_lost(lost).unwrap();
+    directory.terminate(segun).unwrap();
+
+    directory
+}
+
+pub fn handle_swipe(directory: &Directory, line: &str) -> SwipeResponse {
+    let request: SwipeRequest = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => {
+            return SwipeResponse {
+                card_id: None,
+                granted: false,
+                reason: Some(format!("Bad request: {}", e)),
+            };
+        }
+    };
+
+    match directory.check_card(request.card_id, request.zone) {
+        Ok(record) => {
+            println!(
+                "GRANTED card {} ({}) -> {:?}",
+                request.card_id, record.name, request.zone
+            );
+            SwipeResponse {
+                card_id: Some(request.card_id),
+                granted: true,
+                reason: None,
+            }
+        }
+        Err(reason) => {
+            println!(
+                "DENIED  card {} -> {:?}: {}",
+                request.card_id, request.zone, reason
+            );
+            SwipeResponse {
+                card_id: Some(request.card_id),
+                granted: false,
+                reason: Some(reason),
+            }
+        }
+    }
+}
+
+fn handle_connection(directory: &Directory, stream: TcpStream) -> std::io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let reader = BufReader::new(stream);
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = handle_swipe(directory, &line);
+        let reply = serde_json::to_string(&response).map_err(std::io::Error::other)?;
+        writeln!(writer, "{}", reply)?;
+    }
+    Ok(())
+}
+
+// Each reader gets its own thread; the directory is read-only while serving
+pub fn serve(listener: TcpListener, directory: Directory) -> std::io::Result<()> {
+    let directory = Arc::new(directory);
+
+    for stream in listener.incoming() {
+        // A failed accept only loses that client, so keep serving the rest
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Accept error: {}", e);
+                continue;
+            }
+        };
+        let directory = Arc::clone(&directory);
+
+        thread::spawn(move || {
+            if let Err(e) = handle_connection(&directory, stream) {
+                eprintln!("Connection error: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+// Takes the connection's one reader, so replies it has already buffered
+// are still there for the next swipe
+pub fn send_swipe(
+    connection: &mut BufReader<TcpStream>,
+    request: &SwipeRequest,
+) -> Result<SwipeResponse, String> {
+    let line = serde_json::to_string(request).map_err(|e| e.to_string())?;
+    writeln!(connection.get_mut(), "{}", line).map_err(|e| e.to_string())?;
+
+    let mut reply = String::new();
+    connection
+        .read_line(&mut reply)
+        .map_err(|e| e.to_string())?;
+
+    serde_json::from_str(&reply).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bad_json_is_denied() {
+        let directory = demo_directory();
+        let response = handle_swipe(&directory, "not json");
+
+        assert!(!response.granted);
+        assert_eq!(response.card_id, None);
+    }
+
+    #[test]
+    fn swipes_over_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, demo_directory()));
+
+        let mut connection = BufReader::new(TcpStream::connect(addr).unwrap());
+
+        let granted = send_swipe(
+            &mut connection,
+            &SwipeRequest {
+                card_id: 1,
+                zone: Zone::Garage,
+            },
+        )
+        .unwrap();
+        assert!(granted.granted);
+
+        let lost = send_swipe(
+            &mut connection,
+            &SwipeRequest {
+                card_id: 3,
+                zone: Zone::Garage,
+            },
+        )
+        .unwrap();
+        assert!(!lost.granted);
+        assert_eq!(lost.reason, Some("Card was reported lost".to_string()));
+    }
+
+    #[test]
+    fn replies_arriving_together_are_kept() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // Answers both swipes in one write, before the second is sent
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let granted = SwipeResponse {
+                card_id: Some(1),
+                granted: true,
+                reason: None,
+            };
+            let reply = serde_json::to_string(&granted).unwrap();
+            write!(stream, "{}\n{}\n", reply, reply).unwrap();
+
+            let mut lines = BufReader::new(stream).lines();
+            while let Some(Ok(_)) = lines.next() {}
+        });
+
+        let mut connection = BufReader::new(TcpStream::connect(addr).unwrap());
+        let request = SwipeRequest {
+            card_id: 1,
+            zone: Zone::Garage,
+        };
+
+        assert!(send_swipe(&mut connection, &request).unwrap().granted);
+        assert!(send_swipe(&mut connection, &request).unwrap().granted);
+    }
+}
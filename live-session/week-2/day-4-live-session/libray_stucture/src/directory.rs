@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+
+use crate::usage::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CardStatus {
+    Active,
+    Revoked,
+    Lost,
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyCard {
+    pub id: u64,
+    pub employee_id: u32,
+    pub status: CardStatus,
+}
+
+#[derive(Debug, Clone)]
+pub struct EmployeeRecord {
+    pub id: u32,
+    pub name: String,
+    pub employee: Employees,
+    pub cards: Vec<u64>,
+}
+
+// Keeps every employee and every keycard ever issued, so a swipe only
+// needs the card id to find out who is at the door
+#[derive(Debug)]
+pub struct Directory {
+    employees: HashMap<u32, EmployeeRecord>,
+    cards: HashMap<u64, KeyCard>,
+    next_employee_id: u32,
+    next_card_id: u64,
+}
+
+impl Default for Directory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Directory {
+    pub fn new() -> Self {
+        Self {
+            employees: HashMap::new(),
+            cards: HashMap::new(),
+            next_employee_id: 1,
+            next_card_id: 1,
+        }
+    }
+
+    pub fn add_employee(&mut self, name: &str, role: EmployeeType) -> u32 {
+        let id = self.next_employee_id;
+        self.next_employee_id += 1;
+
+        let record = EmployeeRecord {
+            id,
+            name: name.to_string(),
+            employee: Employees {
+                role,
+                status: EmployeeStatus::Active,
+            },
+            cards: Vec::new(),
+        };
+        self.employees.insert(id, record);
+        id
+    }
+
+    pub fn get_employee(&self, employee_id: u32) -> Option<&EmployeeRecord> {
+        self.employees.get(&employee_id)
+    }
+
+    pub fn get_card(&self, card_id: u64) -> Option<&KeyCard> {
+        self.cards.get(&card_id)
+    }
+
+    // Terminating someone also revokes every card they still hold
+    pub fn terminate(&mut self, employee_id: u32) -> Result<(), String> {
+        let record = self
+            .employees
+            .get_mut(&employee_id)
+            .ok_or("Employee not found".to_string())?;
+        record.employee.status = EmployeeStatus::Terminated;
+
+        for card_id in &record.cards {
+            if let Some(card) = self.cards.get_mut(card_id)
+                && card.status == CardStatus::Active
+            {
+                card.status = CardStatus::Revoked;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn issue_card(&mut self, employee_id: u32) -> Result<u64, String> {
+        let record = self
+            .employees
+            .get_mut(&employee_id)
+            .ok_or("Employee not found".to_string())?;
+
+        if let EmployeeStatus::Terminated = record.employee.status {
+            return Err("Cannot issue a card to a terminated employee".to_string());
+        }
+
+        let id = self.next_card_id;
+        self.next_card_id += 1;
+
+        record.cards.push(id);
+        self.cards.insert(
+            id,
+            KeyCard {
+                id,
+                employee_id,
+                status: CardStatus::Active,
+            },
+        );
+        Ok(id)
+    }
+
+    pub fn revoke_card(&mut self, card_id: u64) -> Result<(), String> {
+        self.deactivate_card(card_id, CardStatus::Revoked)
+    }
+
+    pub fn report_lost(&mut self, card_id: u64) -> Result<(), String> {
+        self.deactivate_card(card_id, CardStatus::Lost)
+    }
+
+    fn deactivate_card(&mut self, card_id: u64, status: CardStatus) -> Result<(), String> {
+        let card = self
+            .cards
+            .get_mut(&card_id)
+            .ok_or("Card not found".to_string())?;
+
+        if card.status != CardStatus::Active {
+            return Err(format!("Card is already {:?}", card.status));
+        }
+        card.status = status;
+        Ok(())
+    }
+
+    pub fn check_card(&self, card_id: u64, zone: Zone) -> Result<&EmployeeRecord, String> {
+        let card = self.cards.get(&card_id).ok_or("Unknown card".to_string())?;
+
+        match card.status {
+            CardStatus::Active => (),
+            CardStatus::Revoked => return Err("Card has been revoked".to_string()),
+            CardStatus::Lost => return Err("Card was reported lost".to_string()),
+        };
+
+        let record = self
+            .employees
+            .get(&card.employee_id)
+            .ok_or("Employee not found".to_string())?;
+
+        check_zone_access(&record.employee, zone)?;
+        Ok(record)
+    }
+
+    pub fn attempt_access(&self, card_id: u64, zone: Zone) -> Result<(), String> {
+        let record = self.check_card(card_id, zone)?;
+        println!("{} may enter the {:?}", record.name, zone);
+        print_access(&record.employee);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issued_card_opens_the_garage() {
+        let mut directory = Directory::new();
+        let id = directory.add_employee("Ada", EmployeeType::Manager);
+        let card = directory.issue_card(id).unwrap();
+
+        assert!(directory.attempt_access(card, Zone::Garage).is_ok());
+        assert_eq!(directory.get_employee(id).unwrap().cards, vec![card]);
+    }
+
+    #[test]
+    fn role_without_access_is_denied() {
+        let mut directory = Directory::new();
+        let id = directory.add_employee("Tobi", EmployeeType::SocialMedia);
+        let card = directory.issue_card(id).unwrap();
+
+        assert_eq!(
+            directory.attempt_access(card, Zone::Garage),
+            Err("Access denied".to_string())
+        );
+    }
+
+    #[test]
+    fn lost_and_revoked_cards_are_rejected() {
+        let mut directory = Directory::new();
+        let id = directory.add_employee("Kemi", EmployeeType::ITDepartment);
+        let lost = directory.issue_card(id).unwrap();
+        let revoked = directory.issue_card(id).unwrap();
+        let spare = directory.issue_card(id).unwrap();
+
+        directory.report_lost(lost).unwrap();
+        directory.revoke_card(revoked).unwrap();
+
+        assert!(directory.attempt_access(lost, Zone::Garage).is_err());
+        assert!(directory.attempt_access(revoked, Zone::Garage).is_err());
+        assert!(directory.attempt_access(spare, Zone::ServerRoom).is_ok());
+        assert!(directory.revoke_card(lost).is_err());
+    }
+
+    #[test]
+    fn terminated_employee_loses_every_card() {
+        let mut directory = Directory::new();
+        let id = directory.add_employee("Segun", EmployeeType::MediaTeam);
+        let card = directory.issue_card(id).unwrap();
+
+        directory.terminate(id).unwrap();
+
+        assert_eq!(
+            directory.get_card(card).unwrap().status,
+            CardStatus::Revoked
+        );
+        assert!(directory.attempt_access(card, Zone::Garage).is_err());
+        assert!(directory.issue_card(id).is_err());
+    }
+
+    #[test]
+    fn unknown_card_is_rejected() {
+        let directory = Directory::new();
+        assert_eq!(
+            directory.attempt_access(42, Zone::Garage),
+            Err("Unknown card".to_string())
+        );
+    }
+}
@@ -1,2 +1,4 @@
+pub mod controller;
+pub mod directory;
 pub mod mode;
 pub mod usage;
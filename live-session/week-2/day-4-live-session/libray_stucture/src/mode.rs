@@ -1,5 +1,9 @@
+use crate::directory::Directory;
 use crate::usage::*;
 
-fn do_something(role: EmployeeType, status: EmployeeStatus) {
-    let employee = Employees { role, status };
+pub fn do_something(name: &str, role: EmployeeType) -> Result<(), String> {
+    let mut directory = Directory::new();
+    let id = directory.add_employee(name, role);
+    let card = directory.issue_card(id)?;
+    directory.attempt_access(card, Zone::Garage)
 }
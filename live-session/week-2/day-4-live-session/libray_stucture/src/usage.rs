@@ -1,4 +1,6 @@
-#[derive(Debug)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum EmployeeType {
     MediaTeam,
     ITDepartment,
@@ -8,12 +10,12 @@ pub enum EmployeeType {
     KitchenStaff,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum EmployeeStatus {
     Active,
     Terminated,
 }
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Employees {
     pub role: EmployeeType,
     pub status: EmployeeStatus,
@@ -41,6 +43,36 @@ pub fn check_access(ept: &Employees) -> Result<(), String> {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Zone {
+    Garage,
+    ServerRoom,
+    Kitchen,
+}
+
+pub fn check_zone_access(ept: &Employees, zone: Zone) -> Result<(), String> {
+    // The garage keeps the original rules, the other zones are narrower
+    match zone {
+        Zone::Garage => check_access(ept),
+        Zone::ServerRoom => {
+            check_access(ept)?;
+            match ept.role {
+                EmployeeType::ITDepartment | EmployeeType::Manager => Ok(()),
+                _ => Err("Access denied".to_string()),
+            }
+        }
+        Zone::Kitchen => {
+            if let EmployeeStatus::Terminated = ept.status {
+                return Err("You are not allowed!!".to_string());
+            }
+            match ept.role {
+                EmployeeType::KitchenStaff | EmployeeType::Manager => Ok(()),
+                _ => Err("Access denied".to_string()),
+            }
+        }
+    }
+}
+
 pub fn print_access(print_access: &Employees) {
     println!("The {:?} is {:?}", print_access.role, print_access.status);
 }
@@ -63,4 +95,26 @@ mod tests {
         let result = add(2, 2);
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn zone_rules() {
+        let it = Employees {
+            role: EmployeeType::ITDepartment,
+            status: EmployeeStatus::Active,
+        };
+        let media = Employees {
+            role: EmployeeType::MediaTeam,
+            status: EmployeeStatus::Active,
+        };
+        let cook = Employees {
+            role: EmployeeType::KitchenStaff,
+            status: EmployeeStatus::Active,
+        };
+
+        assert!(check_zone_access(&it, Zone::ServerRoom).is_ok());
+        assert!(check_zone_access(&media, Zone::Garage).is_ok());
+        assert!(check_zone_access(&media, Zone::ServerRoom).is_err());
+        assert!(check_zone_access(&cook, Zone::Garage).is_err());
+        assert!(check_zone_access(&cook, Zone::Kitchen).is_ok());
+    }
 }
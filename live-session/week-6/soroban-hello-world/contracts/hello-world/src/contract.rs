@@ -1,20 +1,160 @@
-use soroban_sdk::{contract, contractimpl, vec, Env, String, Vec};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, vec, Address, Env, Map, String, Symbol, Vec,
+};
+
+use crate::error::GreeterError;
+use crate::ttl;
+
+/// Only the most recent greetings are kept for each caller.
+const HISTORY_LIMIT: u32 = 20;
+
+#[contracttype]
+#[derive(Clone)]
+pub(crate) enum GreetingKey {
+    Admin,
+    DefaultLang,
+    Templates,
+    History(Address),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Greeting {
+    pub to: String,
+    pub lang: Symbol,
+    pub text: String,
+    pub ledger: u32,
+}
 
 #[contract]
 pub struct Contract;
 
-// This is a sample contract. Replace this placeholder with your own contract logic.
-// A corresponding test example is available in `test.rs`.
-//
-// For comprehensive examples, visit <https://github.com/stellar/soroban-examples>.
-// The repository includes use cases for the Stellar ecosystem, such as data storage on
-// the blockchain, token swaps, liquidity pools, and more.
-//
-// Refer to the official documentation:
-// <https://developers.stellar.org/docs/build/smart-contracts/overview>.
 #[contractimpl]
 impl Contract {
-    pub fn hello(env: Env, to: String) -> Vec<String> {
-        vec![&env, String::from_str(&env, "Good morning"), to]
+    /// Sets the admin that manages templates and the language used when a
+    /// caller asks for one that has no template.
+    pub fn init_greeter(
+        env: Env,
+        admin: Address,
+        default_lang: Symbol,
+    ) -> Result<(), GreeterError> {
+        if env.storage().instance().has(&GreetingKey::Admin) {
+            return Err(GreeterError::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&GreetingKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&GreetingKey::DefaultLang, &default_lang);
+        ttl::extend_instance(&env);
+
+        Ok(())
+    }
+
+    /// Admin only, adds or replaces the greeting for a language code
+    /// such as `en` or `yo`.
+    pub fn set_greeting(env: Env, lang: Symbol, template: String) -> Result<(), GreeterError> {
+        Self::admin(&env)?.require_auth();
+        if template.is_empty() {
+            return Err(GreeterError::EmptyTemplate);
+        }
+
+        let mut templates = Self::greetings(env.clone());
+        templates.set(lang.clone(), template.clone());
+        Self::set_templates(&env, &templates);
+
+        env.events()
+            .publish((symbol_short!("set_greet"), lang), template);
+        Ok(())
+    }
+
+    pub fn remove_greeting(env: Env, lang: Symbol) -> Result<(), GreeterError> {
+        Self::admin(&env)?.require_auth();
+
+        let mut templates = Self::greetings(env.clone());
+        if templates.remove(lang.clone()).is_none() {
+            return Err(GreeterError::UnknownLanguage);
+        }
+        Self::set_templates(&env, &templates);
+
+        env.events().publish((symbol_short!("rm_greet"), lang), ());
+        Ok(())
+    }
+
+    pub fn greetings(env: Env) -> Map<Symbol, String> {
+        let templates = env
+            .storage()
+            .persistent()
+            .get(&GreetingKey::Templates)
+            .unwrap_or(Map::new(&env));
+
+        ttl::extend_persistent(&env, &GreetingKey::Templates);
+        templates
+    }
+
+    /// Greets `to` in `lang`, falling back to the default language and then
+    /// to "Good morning", and records the greeting in the caller's history.
+    pub fn hello(env: Env, caller: Address, to: String, lang: Symbol) -> Vec<String> {
+        caller.require_auth();
+
+        let text = Self::template_for(&env, &lang);
+
+        let key = GreetingKey::History(caller);
+        let mut history = Self::load_history(&env, &key);
+        if history.len() >= HISTORY_LIMIT {
+            history.pop_front();
+        }
+        history.push_back(Greeting {
+            to: to.clone(),
+            lang,
+            text: text.clone(),
+            ledger: env.ledger().sequence(),
+        });
+        env.storage().persistent().set(&key, &history);
+        ttl::extend_persistent(&env, &key);
+
+        vec![&env, text, to]
+    }
+
+    pub fn history(env: Env, caller: Address) -> Vec<Greeting> {
+        Self::load_history(&env, &GreetingKey::History(caller))
+    }
+
+    fn template_for(env: &Env, lang: &Symbol) -> String {
+        let templates = Self::greetings(env.clone());
+        if let Some(text) = templates.get(lang.clone()) {
+            return text;
+        }
+
+        let default_lang: Option<Symbol> = env.storage().instance().get(&GreetingKey::DefaultLang);
+        ttl::extend_instance(env);
+
+        default_lang
+            .and_then(|default_lang| templates.get(default_lang))
+            .unwrap_or(String::from_str(env, "Good morning"))
+    }
+
+    fn load_history(env: &Env, key: &GreetingKey) -> Vec<Greeting> {
+        let history = env.storage().persistent().get(key).unwrap_or(Vec::new(env));
+
+        ttl::extend_persistent(env, key);
+        history
+    }
+
+    fn set_templates(env: &Env, templates: &Map<Symbol, String>) {
+        env.storage()
+            .persistent()
+            .set(&GreetingKey::Templates, templates);
+        ttl::extend_persistent(env, &GreetingKey::Templates);
+    }
+
+    fn admin(env: &Env) -> Result<Address, GreeterError> {
+        let admin = env
+            .storage()
+            .instance()
+            .get(&GreetingKey::Admin)
+            .ok_or(GreeterError::NotInitialized)?;
+        ttl::extend_instance(env);
+        Ok(admin)
     }
 }
@@ -0,0 +1,22 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum CounterError {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    InvalidStep = 3,
+    CapReached = 4,
+    Underflow = 5,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum GreeterError {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    EmptyTemplate = 3,
+    UnknownLanguage = 4,
+}
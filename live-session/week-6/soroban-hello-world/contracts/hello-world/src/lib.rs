@@ -1,4 +1,6 @@
 #![no_std]
 mod contract;
+mod error;
 mod storage;
 mod test;
+pub mod ttl;
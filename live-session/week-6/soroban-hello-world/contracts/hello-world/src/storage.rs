@@ -1,21 +1,171 @@
-use soroban_sdk::{contract, contractimpl, log, symbol_short, Env, Symbol};
+use soroban_sdk::{contract, contractimpl, contracttype, log, symbol_short, Address, Env, Vec};
 
-const COUNTER: Symbol = symbol_short!("COUNTER");
+use crate::error::CounterError;
+use crate::ttl;
+
+#[contracttype]
+#[derive(Clone)]
+pub(crate) enum CounterKey {
+    Admin,
+    Step,
+    Cap,
+    Counter(Address),
+}
 
 #[contract]
 pub struct IncrementContract;
 
 #[contractimpl]
 impl IncrementContract {
-    /// Increment increments an internal counter, and returns the value.
-    pub fn increment(env: Env) -> u32 {
-        let mut count: u32 = env.storage().instance().get(&COUNTER).unwrap_or(0);
+    /// Sets the admin, the amount each call moves a counter by and an
+    /// optional upper bound for every counter. Can only be called once.
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        step: u32,
+        cap: Option<u32>,
+    ) -> Result<(), CounterError> {
+        if env.storage().instance().has(&CounterKey::Admin) {
+            return Err(CounterError::AlreadyInitialized);
+        }
+        if step == 0 {
+            return Err(CounterError::InvalidStep);
+        }
+
+        env.storage().instance().set(&CounterKey::Admin, &admin);
+        env.storage().instance().set(&CounterKey::Step, &step);
+        env.storage().instance().set(&CounterKey::Cap, &cap);
+        ttl::extend_instance(&env);
+
+        Ok(())
+    }
+
+    pub fn set_step(env: Env, step: u32) -> Result<(), CounterError> {
+        Self::admin(&env)?.require_auth();
+        if step == 0 {
+            return Err(CounterError::InvalidStep);
+        }
+
+        env.storage().instance().set(&CounterKey::Step, &step);
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("set_step"),), step);
+        Ok(())
+    }
+
+    pub fn set_cap(env: Env, cap: Option<u32>) -> Result<(), CounterError> {
+        Self::admin(&env)?.require_auth();
+
+        env.storage().instance().set(&CounterKey::Cap, &cap);
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("set_cap"),), cap);
+        Ok(())
+    }
+
+    /// Increment increments the caller's counter by the configured step,
+    /// and returns the value.
+    pub fn increment(env: Env, user: Address) -> Result<u32, CounterError> {
+        user.require_auth();
+        let step = Self::step(&env)?;
+
+        let mut count = Self::get_count(env.clone(), user.clone());
         log!(&env, "count: {}", count);
 
-        count += 1;
-        env.storage().instance().set(&COUNTER, &count);
-        env.storage().instance().extend_ttl(50, 100);
+        count = count.checked_add(step).ok_or(CounterError::CapReached)?;
+        if let Some(cap) = Self::cap(&env) {
+            if count > cap {
+                return Err(CounterError::CapReached);
+            }
+        }
+
+        Self::set_count(&env, &user, count);
+        env.events()
+            .publish((symbol_short!("increment"), user), count);
+
+        Ok(count)
+    }
+
+    /// Moves the caller's counter back down by the configured step.
+    pub fn decrement(env: Env, user: Address) -> Result<u32, CounterError> {
+        user.require_auth();
+        let step = Self::step(&env)?;
+
+        let count = Self::get_count(env.clone(), user.clone())
+            .checked_sub(step)
+            .ok_or(CounterError::Underflow)?;
+
+        Self::set_count(&env, &user, count);
+        env.events()
+            .publish((symbol_short!("decrement"), user), count);
 
+        Ok(count)
+    }
+
+    /// Admin only, puts a user's counter back to zero.
+    pub fn reset(env: Env, user: Address) -> Result<(), CounterError> {
+        Self::admin(&env)?.require_auth();
+
+        env.storage()
+            .persistent()
+            .remove(&CounterKey::Counter(user.clone()));
+        ttl::extend_instance(&env);
+        env.events().publish((symbol_short!("reset"), user), 0_u32);
+
+        Ok(())
+    }
+
+    /// Admin only, keeps the contract and the given users' counters alive
+    /// without changing them, e.g. before a long period of inactivity.
+    pub fn bump(env: Env, users: Vec<Address>) -> Result<(), CounterError> {
+        Self::admin(&env)?.require_auth();
+
+        ttl::extend_instance(&env);
+        for user in users.iter() {
+            ttl::extend_persistent(&env, &CounterKey::Counter(user));
+        }
+        Ok(())
+    }
+
+    pub fn get_count(env: Env, user: Address) -> u32 {
+        let key = CounterKey::Counter(user);
+        let count = env.storage().persistent().get(&key).unwrap_or(0);
+
+        ttl::extend_persistent(&env, &key);
+        ttl::extend_instance(&env);
         count
     }
+
+    fn set_count(env: &Env, user: &Address, count: u32) {
+        let key = CounterKey::Counter(user.clone());
+        env.storage().persistent().set(&key, &count);
+
+        ttl::extend_persistent(env, &key);
+        ttl::extend_instance(env);
+    }
+
+    fn admin(env: &Env) -> Result<Address, CounterError> {
+        let admin = env
+            .storage()
+            .instance()
+            .get(&CounterKey::Admin)
+            .ok_or(CounterError::NotInitialized)?;
+        ttl::extend_instance(env);
+        Ok(admin)
+    }
+
+    fn step(env: &Env) -> Result<u32, CounterError> {
+        let step = env
+            .storage()
+            .instance()
+            .get(&CounterKey::Step)
+            .ok_or(CounterError::NotInitialized)?;
+        ttl::extend_instance(env);
+        Ok(step)
+    }
+
+    fn cap(env: &Env) -> Option<u32> {
+        env.storage()
+            .instance()
+            .get(&CounterKey::Cap)
+            .unwrap_or(None)
+    }
 }
@@ -1,15 +1,371 @@
 #![cfg(test)]
 
-use crate::storage::{IncrementContract, IncrementContractClient};
+use crate::contract::{Contract, ContractClient, Greeting};
+use crate::error::{CounterError, GreeterError};
+use crate::storage::{CounterKey, IncrementContract, IncrementContractClient};
+use crate::ttl;
 
-use super::*;
-use soroban_sdk::{vec, Env, String};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{storage::Persistent, Address as _, Events, Ledger},
+    vec, Address, Env, IntoVal, String,
+};
+
+fn setup(step: u32, cap: Option<u32>) -> (Env, IncrementContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(IncrementContract, ());
+    let client = IncrementContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &step, &cap);
+
+    (env, client, admin)
+}
 
 #[test]
 fn test() {
+    let (env, client, _) = setup(1, None);
+    let user = Address::generate(&env);
+
+    assert_eq!(client.increment(&user), 1);
+    assert_eq!(client.increment(&user), 2);
+    assert_eq!(client.get_count(&user), 2);
+}
+
+#[test]
+fn test_counters_are_per_user() {
+    let (env, client, _) = setup(1, None);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    client.increment(&alice);
+    client.increment(&alice);
+    client.increment(&bob);
+
+    assert_eq!(client.get_count(&alice), 2);
+    assert_eq!(client.get_count(&bob), 1);
+}
+
+#[test]
+fn test_increment_requires_user_auth() {
+    let (env, client, _) = setup(1, None);
+    let user = Address::generate(&env);
+
+    client.increment(&user);
+
+    let auths = env.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths[0].0, user);
+}
+
+#[test]
+fn test_step_and_cap() {
+    let (env, client, _) = setup(5, Some(12));
+    let user = Address::generate(&env);
+
+    assert_eq!(client.increment(&user), 5);
+    assert_eq!(client.increment(&user), 10);
+    assert_eq!(
+        client.try_increment(&user),
+        Err(Ok(CounterError::CapReached))
+    );
+
+    client.set_step(&2);
+    assert_eq!(client.increment(&user), 12);
+    assert_eq!(client.decrement(&user), 10);
+}
+
+#[test]
+fn test_decrement_underflow() {
+    let (env, client, _) = setup(3, None);
+    let user = Address::generate(&env);
+
+    client.increment(&user);
+    assert_eq!(client.decrement(&user), 0);
+    assert_eq!(
+        client.try_decrement(&user),
+        Err(Ok(CounterError::Underflow))
+    );
+}
+
+#[test]
+fn test_reset_by_admin() {
+    let (env, client, admin) = setup(1, None);
+    let user = Address::generate(&env);
+
+    client.increment(&user);
+    client.reset(&user);
+
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(client.get_count(&user), 0);
+}
+
+#[test]
+fn test_increment_event() {
+    let (env, client, _) = setup(1, None);
+    let user = Address::generate(&env);
+
+    client.increment(&user);
+
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("increment"), user).into_val(&env),
+                1_u32.into_val(&env)
+            )
+        ]
+    );
+}
+
+#[test]
+fn test_initialize_once() {
+    let (env, client, _) = setup(1, None);
+    let other = Address::generate(&env);
+
+    assert_eq!(
+        client.try_initialize(&other, &1, &None),
+        Err(Ok(CounterError::AlreadyInitialized))
+    );
+}
+
+#[test]
+fn test_not_initialized() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register(IncrementContract, ());
     let client = IncrementContractClient::new(&env, &contract_id);
+    let user = Address::generate(&env);
+
+    assert_eq!(
+        client.try_increment(&user),
+        Err(Ok(CounterError::NotInitialized))
+    );
+}
+
+fn advance_ledgers(env: &Env, ledgers: u32) {
+    env.ledger().with_mut(|li| li.sequence_number += ledgers);
+}
+
+fn counter_ttl(env: &Env, client: &IncrementContractClient, user: &Address) -> u32 {
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .get_ttl(&CounterKey::Counter(user.clone()))
+    })
+}
+
+#[test]
+fn test_write_extends_counter_ttl() {
+    let (env, client, _) = setup(1, None);
+    let user = Address::generate(&env);
+
+    client.increment(&user);
+
+    assert_eq!(counter_ttl(&env, &client, &user), ttl::PERSISTENT.extend_to);
+}
+
+#[test]
+fn test_read_extends_counter_ttl() {
+    let (env, client, _) = setup(1, None);
+    let user = Address::generate(&env);
+    client.increment(&user);
+
+    // Under the threshold, so the next read has to bump it again
+    advance_ledgers(&env, 2 * ttl::DAY_IN_LEDGERS);
+    assert_eq!(
+        counter_ttl(&env, &client, &user),
+        ttl::PERSISTENT.extend_to - 2 * ttl::DAY_IN_LEDGERS
+    );
+
+    assert_eq!(client.get_count(&user), 1);
+    assert_eq!(counter_ttl(&env, &client, &user), ttl::PERSISTENT.extend_to);
+}
+
+#[test]
+fn test_active_counter_survives() {
+    let (env, client, _) = setup(1, None);
+    let user = Address::generate(&env);
+
+    // Touch the counter every few days for two months
+    for _ in 0..20 {
+        client.increment(&user);
+        advance_ledgers(&env, 3 * ttl::DAY_IN_LEDGERS);
+    }
+
+    assert_eq!(client.get_count(&user), 20);
+}
+
+#[test]
+fn test_admin_bump_keeps_counter_alive() {
+    let (env, client, _) = setup(1, None);
+    let user = Address::generate(&env);
+    client.increment(&user);
+
+    for _ in 0..10 {
+        advance_ledgers(&env, 5 * ttl::DAY_IN_LEDGERS);
+        client.bump(&vec![&env, user.clone()]);
+    }
+
+    assert_eq!(client.get_count(&user), 1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Storage, InternalError)")]
+fn test_idle_counter_is_archived() {
+    let (env, client, _) = setup(1, None);
+    let user = Address::generate(&env);
+    client.increment(&user);
+
+    // Keep the instance alive but never touch the counter
+    for _ in 0..5 {
+        advance_ledgers(&env, 7 * ttl::DAY_IN_LEDGERS);
+        client.bump(&vec![&env]);
+    }
+
+    client.get_count(&user);
+}
+
+fn setup_greeter() -> (Env, ContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(Contract, ());
+    let client = ContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.init_greeter(&admin, &symbol_short!("en"));
+    client.set_greeting(
+        &symbol_short!("en"),
+        &String::from_str(&env, "Good morning"),
+    );
+    client.set_greeting(&symbol_short!("yo"), &String::from_str(&env, "E kaaro"));
+
+    (env, client, admin)
+}
+
+#[test]
+fn test_hello_in_requested_language() {
+    let (env, client, _) = setup_greeter();
+    let caller = Address::generate(&env);
+    let to = String::from_str(&env, "Ada");
+
+    assert_eq!(
+        client.hello(&caller, &to, &symbol_short!("yo")),
+        vec![&env, String::from_str(&env, "E kaaro"), to]
+    );
+}
+
+#[test]
+fn test_hello_falls_back_to_default_language() {
+    let (env, client, _) = setup_greeter();
+    let caller = Address::generate(&env);
+    let to = String::from_str(&env, "Ada");
+
+    assert_eq!(
+        client.hello(&caller, &to, &symbol_short!("fr")),
+        vec![&env, String::from_str(&env, "Good morning"), to]
+    );
+}
+
+#[test]
+fn test_hello_without_templates() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(Contract, ());
+    let client = ContractClient::new(&env, &contract_id);
+    let caller = Address::generate(&env);
+    let to = String::from_str(&env, "Dev");
+
+    let words = client.hello(&caller, &to, &symbol_short!("en"));
+
+    assert_eq!(
+        words,
+        vec![&env, String::from_str(&env, "Good morning"), to]
+    );
+}
+
+#[test]
+fn test_admin_manages_templates() {
+    let (env, client, admin) = setup_greeter();
+
+    client.set_greeting(&symbol_short!("ig"), &String::from_str(&env, "Ututu oma"));
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(client.greetings().len(), 3);
+
+    client.remove_greeting(&symbol_short!("yo"));
+    assert_eq!(client.greetings().get(symbol_short!("yo")), None);
+
+    assert_eq!(
+        client.try_remove_greeting(&symbol_short!("yo")),
+        Err(Ok(GreeterError::UnknownLanguage))
+    );
+    assert_eq!(
+        client.try_set_greeting(&symbol_short!("fr"), &String::from_str(&env, "")),
+        Err(Ok(GreeterError::EmptyTemplate))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_set_greeting_requires_admin() {
+    let (env, client, _) = setup_greeter();
+    env.set_auths(&[]);
+
+    client.set_greeting(&symbol_short!("fr"), &String::from_str(&env, "Bonjour"));
+}
+
+#[test]
+fn test_init_greeter_once() {
+    let (env, client, _) = setup_greeter();
+
+    assert_eq!(
+        client.try_init_greeter(&Address::generate(&env), &symbol_short!("yo")),
+        Err(Ok(GreeterError::AlreadyInitialized))
+    );
+}
+
+#[test]
+fn test_history_per_caller() {
+    let (env, client, _) = setup_greeter();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let to = String::from_str(&env, "Ada");
+
+    client.hello(&alice, &to, &symbol_short!("yo"));
+    client.hello(&alice, &to, &symbol_short!("en"));
+    client.hello(&bob, &to, &symbol_short!("en"));
+
+    let history = client.history(&alice);
+    assert_eq!(history.len(), 2);
+    assert_eq!(
+        history.get(0).unwrap(),
+        Greeting {
+            to: to.clone(),
+            lang: symbol_short!("yo"),
+            text: String::from_str(&env, "E kaaro"),
+            ledger: env.ledger().sequence(),
+        }
+    );
+    assert_eq!(client.history(&bob).len(), 1);
+}
+
+#[test]
+fn test_history_is_bounded() {
+    let (env, client, _) = setup_greeter();
+    let caller = Address::generate(&env);
+
+    for _ in 0..25 {
+        client.hello(
+            &caller,
+            &String::from_str(&env, "Ada"),
+            &symbol_short!("en"),
+        );
+    }
 
-    let words = client.increment();
+    assert_eq!(client.history(&caller).len(), 20);
 }
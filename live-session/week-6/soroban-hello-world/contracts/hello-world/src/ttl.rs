@@ -0,0 +1,66 @@
+//! Storage lifetime policy for the contracts in this crate.
+//!
+//! Every entry point extends the TTL of whatever it touches, so an entry
+//! only gets archived when nobody used it for a whole bump period. Copy this
+//! module instead of sprinkling `extend_ttl(50, 100)` around: those numbers
+//! are a few minutes of ledgers and the data disappears on testnet.
+use soroban_sdk::{Env, IntoVal, Val};
+
+/// Roughly one day at ~5 second ledgers.
+pub const DAY_IN_LEDGERS: u32 = 17280;
+
+/// When an entry's remaining TTL drops below `threshold` it is extended so
+/// that it lives for `extend_to` ledgers from now.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TtlPolicy {
+    pub threshold: u32,
+    pub extend_to: u32,
+}
+
+/// Contract config (admin, step, cap, ...), shared by every caller.
+pub const INSTANCE: TtlPolicy = TtlPolicy {
+    threshold: 6 * DAY_IN_LEDGERS,
+    extend_to: 7 * DAY_IN_LEDGERS,
+};
+
+/// Per-user data that must never be lost.
+pub const PERSISTENT: TtlPolicy = TtlPolicy {
+    threshold: 29 * DAY_IN_LEDGERS,
+    extend_to: 30 * DAY_IN_LEDGERS,
+};
+
+/// Short lived data that is fine to lose, e.g. caches or nonces.
+pub const TEMPORARY: TtlPolicy = TtlPolicy {
+    threshold: DAY_IN_LEDGERS / 2,
+    extend_to: DAY_IN_LEDGERS,
+};
+
+pub fn extend_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE.threshold, INSTANCE.extend_to);
+}
+
+/// Does nothing if the entry does not exist.
+pub fn extend_persistent<K>(env: &Env, key: &K)
+where
+    K: IntoVal<Env, Val>,
+{
+    if env.storage().persistent().has(key) {
+        env.storage()
+            .persistent()
+            .extend_ttl(key, PERSISTENT.threshold, PERSISTENT.extend_to);
+    }
+}
+
+/// Does nothing if the entry does not exist.
+pub fn extend_temporary<K>(env: &Env, key: &K)
+where
+    K: IntoVal<Env, Val>,
+{
+    if env.storage().temporary().has(key) {
+        env.storage()
+            .temporary()
+            .extend_ttl(key, TEMPORARY.threshold, TEMPORARY.extend_to);
+    }
+}
@@ -0,0 +1,169 @@
+use soroban_sdk::{contractimpl, contracttype, token, Address, Env, String};
+
+use crate::error::TodoError;
+use crate::events;
+use crate::todo_list::{DataKey, Todo, TodoMeta};
+use crate::{Todolist, TodolistArgs, TodolistClient};
+
+/// Tokens to escrow when creating a todo. `token` can be any SEP-41 token
+/// contract.
+#[contracttype]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Reward {
+    pub token: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Bounty {
+    pub creator: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub assignee: Option<Address>,
+}
+
+// A todo with a bounty is paid out when its creator marks it `Done`, and
+// refunded to the creator when it is deleted. It cannot be archived while
+// the reward is still in escrow.
+#[contractimpl]
+impl Todolist {
+    /// Creates a todo and moves `reward.amount` of `reward.token` from the
+    /// caller into the contract until the todo is completed or deleted.
+    /// Tokens that charge a fee on transfer deliver less than that, so the
+    /// bounty is what the contract actually received.
+    pub fn create_bounty(
+        env: Env,
+        caller: Address,
+        owner: Address,
+        title: String,
+        description: String,
+        meta: TodoMeta,
+        reward: Reward,
+    ) -> Result<Todo, TodoError> {
+        if reward.amount <= 0 {
+            return Err(TodoError::InvalidAmount);
+        }
+
+        let todo = Self::create_todo(
+            env.clone(),
+            caller.clone(),
+            owner.clone(),
+            title,
+            description,
+            meta,
+        )?;
+
+        let token = token::TokenClient::new(&env, &reward.token);
+        let escrow = env.current_contract_address();
+        let before = token.balance(&escrow);
+        token.transfer(&caller, &escrow, &reward.amount);
+        let received = token.balance(&escrow) - before;
+        if received <= 0 {
+            return Err(TodoError::InvalidAmount);
+        }
+
+        let bounty = Bounty {
+            creator: caller,
+            token: reward.token,
+            amount: received,
+            assignee: None,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Bounty(owner.clone(), todo.id), &bounty);
+
+        events::bounty_created_event(&env, owner, todo.id, &bounty);
+        Ok(todo)
+    }
+
+    /// Sets who gets paid once the creator confirms the todo is done.
+    pub fn assign_todo(
+        env: Env,
+        caller: Address,
+        owner: Address,
+        id: u32,
+        assignee: Address,
+    ) -> Result<(), TodoError> {
+        caller.require_auth();
+
+        let mut bounty = Self::get_bounty(env.clone(), owner.clone(), id)?;
+        if caller != bounty.creator {
+            return Err(TodoError::Unauthorized);
+        }
+
+        bounty.assignee = Some(assignee.clone());
+        env.storage()
+            .persistent()
+            .set(&DataKey::Bounty(owner.clone(), id), &bounty);
+
+        events::bounty_assigned_event(&env, owner, id, assignee);
+        Ok(())
+    }
+
+    pub fn get_bounty(env: Env, owner: Address, id: u32) -> Result<Bounty, TodoError> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Bounty(owner, id))
+            .ok_or(TodoError::NoBounty)
+    }
+}
+
+fn load(env: &Env, owner: &Address, id: u32) -> Option<Bounty> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Bounty(owner.clone(), id))
+}
+
+/// Pays the assignee when the creator completes the todo. Todos without a
+/// bounty are left alone.
+pub(crate) fn release(
+    env: &Env,
+    caller: &Address,
+    owner: &Address,
+    id: u32,
+) -> Result<(), TodoError> {
+    let bounty = match load(env, owner, id) {
+        Some(bounty) => bounty,
+        None => return Ok(()),
+    };
+
+    if *caller != bounty.creator {
+        return Err(TodoError::Unauthorized);
+    }
+    let assignee = bounty.assignee.ok_or(TodoError::NoAssignee)?;
+
+    token::TokenClient::new(env, &bounty.token).transfer(
+        &env.current_contract_address(),
+        &assignee,
+        &bounty.amount,
+    );
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Bounty(owner.clone(), id));
+
+    events::bounty_paid_event(env, owner.clone(), id, assignee, bounty.amount);
+    Ok(())
+}
+
+/// Gives the escrow back to the creator, used when the todo is deleted.
+pub(crate) fn refund(env: &Env, owner: &Address, id: u32) {
+    if let Some(bounty) = load(env, owner, id) {
+        token::TokenClient::new(env, &bounty.token).transfer(
+            &env.current_contract_address(),
+            &bounty.creator,
+            &bounty.amount,
+        );
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Bounty(owner.clone(), id));
+
+        events::bounty_refunded_event(env, owner.clone(), id, bounty.creator, bounty.amount);
+    }
+}
+
+pub(crate) fn is_pending(env: &Env, owner: &Address, id: u32) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::Bounty(owner.clone(), id))
+}
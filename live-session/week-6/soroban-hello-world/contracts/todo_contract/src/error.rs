@@ -0,0 +1,19 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum TodoError {
+    NotFound = 1,
+    Unauthorized = 2,
+    EmptyTitle = 3,
+    TitleTooLong = 4,
+    AlreadyCompleted = 5,
+    DescriptionTooLong = 6,
+    InvalidTransition = 7,
+    TooManyTags = 8,
+    InvalidAmount = 9,
+    NoBounty = 10,
+    NoAssignee = 11,
+    BountyPending = 12,
+}
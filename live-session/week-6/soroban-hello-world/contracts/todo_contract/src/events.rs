@@ -0,0 +1,49 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::bounty::Bounty;
+use crate::todo_list::{Todo, TodoStatus};
+
+// Every event has the todo's owner and id as its last two topics, so an
+// indexer can key its rows on them without decoding the data. Completing a
+// todo is a `status` event whose new status is `Done`.
+
+pub fn todo_created_event(env: &Env, owner: Address, todo: &Todo) {
+    let topics = (symbol_short!("created"), owner, todo.id);
+    env.events().publish(topics, todo.clone());
+}
+
+pub fn todo_updated_event(env: &Env, owner: Address, todo: &Todo) {
+    let topics = (symbol_short!("updated"), owner, todo.id);
+    env.events().publish(topics, todo.clone());
+}
+
+pub fn todo_status_event(env: &Env, owner: Address, id: u32, from: TodoStatus, to: TodoStatus) {
+    let topics = (symbol_short!("status"), owner, id);
+    env.events().publish(topics, (from, to));
+}
+
+pub fn todo_deleted_event(env: &Env, owner: Address, id: u32) {
+    let topics = (symbol_short!("deleted"), owner, id);
+    env.events().publish(topics, ());
+}
+
+pub fn bounty_created_event(env: &Env, owner: Address, id: u32, bounty: &Bounty) {
+    let topics = (symbol_short!("bounty"), owner, id);
+    env.events()
+        .publish(topics, (bounty.token.clone(), bounty.amount));
+}
+
+pub fn bounty_assigned_event(env: &Env, owner: Address, id: u32, assignee: Address) {
+    let topics = (symbol_short!("assigned"), owner, id);
+    env.events().publish(topics, assignee);
+}
+
+pub fn bounty_paid_event(env: &Env, owner: Address, id: u32, assignee: Address, amount: i128) {
+    let topics = (symbol_short!("paid"), owner, id);
+    env.events().publish(topics, (assignee, amount));
+}
+
+pub fn bounty_refunded_event(env: &Env, owner: Address, id: u32, creator: Address, amount: i128) {
+    let topics = (symbol_short!("refunded"), owner, id);
+    env.events().publish(topics, (creator, amount));
+}
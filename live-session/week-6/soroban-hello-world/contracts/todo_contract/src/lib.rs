@@ -1,3 +1,13 @@
 #![no_std]
+use soroban_sdk::contract;
+
+pub mod bounty;
+pub mod error;
+pub mod events;
 mod test;
-mod todo_list;
+pub mod todo_list;
+
+// Declared here so the bounty and todo list entry points, which live in
+// their own modules, share one contract and client.
+#[contract]
+pub struct Todolist;
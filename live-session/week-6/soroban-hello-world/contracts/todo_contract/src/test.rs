@@ -1,39 +1,81 @@
 #![cfg(test)]
 
-use crate::todo_list::{Todolist, TodolistClient};
+extern crate std;
 
-use soroban_sdk::{Env, String};
+use crate::bounty::Reward;
+use crate::error::TodoError;
+use crate::todo_list::{
+    DataKey, LegacyDataKey, LegacyTodo, Priority, Todo, TodoMeta, TodoStatus, Todolist,
+    TodolistClient,
+};
 
-fn setup() -> (Env, TodolistClient<'static>) {
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke},
+    token::{StellarAssetClient, TokenClient},
+    vec,
+    xdr::{Limits, ScEnvMetaEntry, ScEnvMetaEntryInterfaceVersion, WriteXdr},
+    Address, Bytes, Env, IntoVal, String, Symbol, Vec,
+};
+
+fn setup_with_admin() -> (Env, TodolistClient<'static>, Address, Address) {
     let env = Env::default();
-    let contract_id = env.register(Todolist, ());
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(Todolist, (admin.clone(),));
     let client = TodolistClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+
+    (env, client, owner, admin)
+}
+
+fn setup() -> (Env, TodolistClient<'static>, Address) {
+    let (env, client, owner, _) = setup_with_admin();
+    (env, client, owner)
+}
+
+fn no_tags(env: &Env) -> Vec<Symbol> {
+    Vec::new(env)
+}
 
-    (env, client)
+fn meta(priority: Priority, due_ledger_timestamp: Option<u64>, tags: Vec<Symbol>) -> TodoMeta {
+    TodoMeta {
+        priority,
+        due_ledger_timestamp,
+        tags,
+    }
 }
 
 #[test]
 fn test() {
-    let (env, client) = setup();
+    let (env, client, owner) = setup();
 
     let title = String::from_str(&env, "Go home!!!");
 
     let description = String::from_str(&env, "From Garage to the hostel");
 
-    let words = client.create_todo(&title, &description);
+    let words = client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::Medium, None, no_tags(&env)),
+    );
 
-    let all_todo = client.get_todos_enum();
+    let all_todo = client.get_todos_enum(&owner);
 
     assert_eq!(all_todo.len(), 1);
     assert_eq!(words.description, description);
     assert_eq!(words.title, title);
     assert_eq!(words.id, 1);
-    assert!(!words.status);
+    assert_eq!(words.status, TodoStatus::Open);
+    assert_eq!(words.priority, Priority::Medium);
 }
 
 #[test]
 fn test_delete() {
-    let (env, client) = setup();
+    let (env, client, owner) = setup();
 
     let title = String::from_str(&env, "Go home!!!");
 
@@ -41,15 +83,1056 @@ fn test_delete() {
 
     let description = String::from_str(&env, "From Garage to the hostel");
 
-    client.create_todo(&title, &description);
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::Medium, None, no_tags(&env)),
+    );
 
-    let all_todo = client.get_todos_enum();
+    let all_todo = client.get_todos_enum(&owner);
 
     assert_eq!(all_todo.len(), 1);
 
-    client.delete_todo(&id);
+    client.delete_todo(&owner, &owner, &id);
 
-    let all_todo = client.get_todos();
+    let all_todo = client.get_todos(&owner, &0, &10);
 
     assert_eq!(all_todo.len(), 0);
+    assert_eq!(
+        client.try_get_todo(&owner, &id),
+        Err(Ok(TodoError::NotFound))
+    );
+}
+
+#[test]
+fn test_lists_are_per_owner() {
+    let (env, client, owner) = setup();
+    let other = Address::generate(&env);
+
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::Medium, None, no_tags(&env)),
+    );
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::Medium, None, no_tags(&env)),
+    );
+    let first = client.create_todo(
+        &other,
+        &other,
+        &title,
+        &description,
+        &meta(Priority::Medium, None, no_tags(&env)),
+    );
+
+    assert_eq!(client.get_todos_enum(&owner).len(), 2);
+    assert_eq!(client.get_todos_enum(&other).len(), 1);
+    assert_eq!(first.id, 1);
+}
+
+#[test]
+fn test_mutation_requires_caller_auth() {
+    let (env, client, owner) = setup();
+
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::Medium, None, no_tags(&env)),
+    );
+    assert_eq!(env.auths()[0].0, owner);
+
+    client.complete_todo(&owner, &owner, &1);
+    assert_eq!(env.auths()[0].0, owner);
+}
+
+#[test]
+fn test_stranger_cannot_edit() {
+    let (env, client, owner) = setup();
+    let stranger = Address::generate(&env);
+
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::Medium, None, no_tags(&env)),
+    );
+    assert_eq!(
+        client.try_complete_todo(&stranger, &owner, &1),
+        Err(Ok(TodoError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_create_todo(
+            &stranger,
+            &owner,
+            &title,
+            &description,
+            &meta(Priority::Medium, None, no_tags(&env))
+        ),
+        Err(Ok(TodoError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_shared_editor_can_edit() {
+    let (env, client, owner) = setup();
+    let editor = Address::generate(&env);
+
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+
+    client.share_list(&owner, &editor);
+    assert_eq!(client.get_editors(&owner).len(), 1);
+
+    client.create_todo(
+        &editor,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::Medium, None, no_tags(&env)),
+    );
+    client.complete_todo(&editor, &owner, &1);
+
+    let todo = client.get_todos_enum(&owner).get(0).unwrap();
+    assert_eq!(todo.status, TodoStatus::Done);
+    assert!(client.get_todos_enum(&editor).is_empty());
+}
+
+#[test]
+fn test_unshared_editor_loses_access() {
+    let (env, client, owner) = setup();
+    let editor = Address::generate(&env);
+
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+
+    client.share_list(&owner, &editor);
+    client.create_todo(
+        &editor,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::Medium, None, no_tags(&env)),
+    );
+
+    client.unshare_list(&owner, &editor);
+    assert!(client.get_editors(&owner).is_empty());
+
+    assert_eq!(
+        client.try_delete_todo(&editor, &owner, &1),
+        Err(Ok(TodoError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_title_and_description_validation() {
+    let (env, client, owner) = setup();
+    let description = String::from_str(&env, "From Garage to the hostel");
+
+    assert_eq!(
+        client.try_create_todo(
+            &owner,
+            &owner,
+            &String::from_str(&env, ""),
+            &description,
+            &meta(Priority::Medium, None, no_tags(&env))
+        ),
+        Err(Ok(TodoError::EmptyTitle))
+    );
+
+    let long_title = String::from_bytes(&env, &[b'a'; 65]);
+    assert_eq!(
+        client.try_create_todo(
+            &owner,
+            &owner,
+            &long_title,
+            &description,
+            &meta(Priority::Medium, None, no_tags(&env))
+        ),
+        Err(Ok(TodoError::TitleTooLong))
+    );
+
+    let long_description = String::from_bytes(&env, &[b'a'; 257]);
+    let title = String::from_str(&env, "Go home!!!");
+    assert_eq!(
+        client.try_create_todo(
+            &owner,
+            &owner,
+            &title,
+            &long_description,
+            &meta(Priority::Medium, None, no_tags(&env))
+        ),
+        Err(Ok(TodoError::DescriptionTooLong))
+    );
+
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::Medium, None, no_tags(&env)),
+    );
+    assert_eq!(
+        client.try_update_todo(
+            &owner,
+            &owner,
+            &1,
+            &String::from_str(&env, ""),
+            &description
+        ),
+        Err(Ok(TodoError::EmptyTitle))
+    );
+}
+
+#[test]
+fn test_missing_todo_is_not_found() {
+    let (env, client, owner) = setup();
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+
+    assert_eq!(
+        client.try_update_todo(&owner, &owner, &7, &title, &description),
+        Err(Ok(TodoError::NotFound))
+    );
+    assert_eq!(
+        client.try_complete_todo(&owner, &owner, &7),
+        Err(Ok(TodoError::NotFound))
+    );
+    assert_eq!(
+        client.try_delete_todo(&owner, &owner, &7),
+        Err(Ok(TodoError::NotFound))
+    );
+}
+
+#[test]
+fn test_complete_twice() {
+    let (env, client, owner) = setup();
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::Medium, None, no_tags(&env)),
+    );
+    client.complete_todo(&owner, &owner, &1);
+
+    assert_eq!(client.get_todo(&owner, &1).status, TodoStatus::Done);
+    assert_eq!(
+        client.try_complete_todo(&owner, &owner, &1),
+        Err(Ok(TodoError::AlreadyCompleted))
+    );
+}
+
+#[test]
+fn test_status_transitions() {
+    let (env, client, owner) = setup();
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::Medium, None, no_tags(&env)),
+    );
+
+    client.set_status(&owner, &owner, &1, &TodoStatus::InProgress);
+    client.set_status(&owner, &owner, &1, &TodoStatus::Done);
+    client.set_status(&owner, &owner, &1, &TodoStatus::Open);
+    client.complete_todo(&owner, &owner, &1);
+    client.set_status(&owner, &owner, &1, &TodoStatus::Archived);
+
+    assert_eq!(client.get_todo(&owner, &1).status, TodoStatus::Archived);
+    assert_eq!(
+        client.try_set_status(&owner, &owner, &1, &TodoStatus::Open),
+        Err(Ok(TodoError::InvalidTransition))
+    );
+    assert_eq!(
+        client.try_complete_todo(&owner, &owner, &1),
+        Err(Ok(TodoError::InvalidTransition))
+    );
+}
+
+#[test]
+fn test_status_change_event() {
+    let (env, client, owner) = setup();
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::Medium, None, no_tags(&env)),
+    );
+
+    client.set_status(&owner, &owner, &1, &TodoStatus::InProgress);
+
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("status"), owner.clone(), 1_u32).into_val(&env),
+                (TodoStatus::Open, TodoStatus::InProgress).into_val(&env)
+            )
+        ]
+    );
+}
+
+#[test]
+fn test_create_update_delete_events() {
+    let (env, client, owner) = setup();
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+
+    let todo = client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::Medium, None, no_tags(&env)),
+    );
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("created"), owner.clone(), 1_u32).into_val(&env),
+                todo.into_val(&env)
+            )
+        ]
+    );
+
+    let sleep = String::from_str(&env, "Sleep");
+    client.update_todo(&owner, &owner, &1, &sleep, &description);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("updated"), owner.clone(), 1_u32).into_val(&env),
+                client.get_todo(&owner, &1).into_val(&env)
+            )
+        ]
+    );
+
+    client.delete_todo(&owner, &owner, &1);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("deleted"), owner.clone(), 1_u32).into_val(&env),
+                ().into_val(&env)
+            )
+        ]
+    );
+}
+
+#[test]
+fn test_priority_due_date_and_tags() {
+    let (env, client, owner) = setup();
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+    let tags = vec![&env, symbol_short!("garage"), symbol_short!("evening")];
+
+    let todo = client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::High, Some(1_000), tags.clone()),
+    );
+    assert_eq!(todo.priority, Priority::High);
+    assert_eq!(todo.due_ledger_timestamp, Some(1_000));
+    assert_eq!(todo.tags, tags);
+
+    client.update_meta(
+        &owner,
+        &owner,
+        &1,
+        &meta(Priority::Low, None, no_tags(&env)),
+    );
+    let todo = client.get_todo(&owner, &1);
+    assert_eq!(todo.priority, Priority::Low);
+    assert_eq!(todo.due_ledger_timestamp, None);
+    assert!(todo.tags.is_empty());
+
+    let too_many = vec![
+        &env,
+        symbol_short!("a"),
+        symbol_short!("b"),
+        symbol_short!("c"),
+        symbol_short!("d"),
+        symbol_short!("e"),
+        symbol_short!("f"),
+    ];
+    assert_eq!(
+        client.try_create_todo(
+            &owner,
+            &owner,
+            &title,
+            &description,
+            &meta(Priority::Low, None, too_many)
+        ),
+        Err(Ok(TodoError::TooManyTags))
+    );
+}
+
+#[test]
+fn test_overdue() {
+    let (env, client, owner) = setup();
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+    env.ledger().set_timestamp(1_000);
+
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::High, Some(2_000), no_tags(&env)),
+    );
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::Low, Some(5_000), no_tags(&env)),
+    );
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::Low, None, no_tags(&env)),
+    );
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(Priority::Low, Some(1_500), no_tags(&env)),
+    );
+    client.complete_todo(&owner, &owner, &4);
+
+    assert!(client.get_overdue(&owner).is_empty());
+
+    env.ledger().set_timestamp(3_000);
+    let overdue = client.get_overdue(&owner);
+    assert_eq!(overdue.len(), 1);
+    assert_eq!(overdue.get(0).unwrap().id, 1);
+
+    env.ledger().set_timestamp(6_000);
+    assert_eq!(client.get_overdue(&owner).len(), 2);
+}
+
+// A SEP-41 token with `amount` minted to `holder`
+fn reward_token<'a>(env: &Env, holder: &Address, amount: i128) -> TokenClient<'a> {
+    let admin = Address::generate(env);
+    let sac = env.register_stellar_asset_contract_v2(admin);
+    StellarAssetClient::new(env, &sac.address()).mint(holder, &amount);
+
+    TokenClient::new(env, &sac.address())
+}
+
+fn create_bounty(
+    env: &Env,
+    client: &TodolistClient,
+    owner: &Address,
+    token: &TokenClient,
+    amount: i128,
+) -> Todo {
+    client.create_bounty(
+        owner,
+        owner,
+        &String::from_str(env, "Fix the garage door"),
+        &String::from_str(env, "It does not close"),
+        &meta(Priority::High, None, no_tags(env)),
+        &Reward {
+            token: token.address.clone(),
+            amount,
+        },
+    )
+}
+
+#[test]
+fn test_bounty_paid_to_assignee() {
+    let (env, client, owner) = setup();
+    let assignee = Address::generate(&env);
+    let token = reward_token(&env, &owner, 1_000);
+
+    let todo = create_bounty(&env, &client, &owner, &token, 300);
+    assert_eq!(token.balance(&owner), 700);
+    assert_eq!(token.balance(&client.address), 300);
+
+    client.assign_todo(&owner, &owner, &todo.id, &assignee);
+    client.complete_todo(&owner, &owner, &todo.id);
+
+    assert_eq!(token.balance(&assignee), 300);
+    assert_eq!(token.balance(&client.address), 0);
+    assert_eq!(
+        client.try_get_bounty(&owner, &todo.id),
+        Err(Ok(TodoError::NoBounty))
+    );
+    assert_eq!(client.get_todo(&owner, &todo.id).status, TodoStatus::Done);
+}
+
+#[test]
+fn test_bounty_refunded_on_delete() {
+    let (env, client, owner) = setup();
+    let token = reward_token(&env, &owner, 1_000);
+
+    let todo = create_bounty(&env, &client, &owner, &token, 300);
+    client.delete_todo(&owner, &owner, &todo.id);
+
+    assert_eq!(token.balance(&owner), 1_000);
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+#[test]
+fn test_bounty_needs_creator_and_assignee() {
+    let (env, client, owner) = setup();
+    let editor = Address::generate(&env);
+    let token = reward_token(&env, &owner, 1_000);
+    client.share_list(&owner, &editor);
+
+    let todo = create_bounty(&env, &client, &owner, &token, 300);
+
+    assert_eq!(
+        client.try_complete_todo(&owner, &owner, &todo.id),
+        Err(Ok(TodoError::NoAssignee))
+    );
+
+    client.assign_todo(&owner, &owner, &todo.id, &editor);
+    assert_eq!(
+        client.try_complete_todo(&editor, &owner, &todo.id),
+        Err(Ok(TodoError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_assign_todo(&editor, &owner, &todo.id, &editor),
+        Err(Ok(TodoError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_status(&owner, &owner, &todo.id, &TodoStatus::Archived),
+        Err(Ok(TodoError::BountyPending))
+    );
+
+    // The assignee can still report progress
+    client.set_status(&editor, &owner, &todo.id, &TodoStatus::InProgress);
+    assert_eq!(token.balance(&client.address), 300);
+}
+
+#[test]
+fn test_bounty_amount_must_be_positive() {
+    let (env, client, owner) = setup();
+    let token = reward_token(&env, &owner, 1_000);
+
+    assert_eq!(
+        client.try_create_bounty(
+            &owner,
+            &owner,
+            &String::from_str(&env, "Fix the garage door"),
+            &String::from_str(&env, "It does not close"),
+            &meta(Priority::High, None, no_tags(&env)),
+            &Reward {
+                token: token.address.clone(),
+                amount: 0,
+            },
+        ),
+        Err(Ok(TodoError::InvalidAmount))
+    );
+    assert!(client.get_todos_enum(&owner).is_empty());
+}
+
+#[test]
+fn test_bounty_events() {
+    let (env, client, owner) = setup();
+    let assignee = Address::generate(&env);
+    let token = reward_token(&env, &owner, 1_000);
+    let todo = create_bounty(&env, &client, &owner, &token, 300);
+
+    client.assign_todo(&owner, &owner, &todo.id, &assignee);
+    client.complete_todo(&owner, &owner, &todo.id);
+
+    // The token's own transfer event comes first, then the payout and the
+    // status change
+    let events = env.events().all();
+    assert_eq!(
+        events.slice(1..2),
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("paid"), owner.clone(), todo.id).into_val(&env),
+                (assignee, 300_i128).into_val(&env)
+            )
+        ]
+    );
+}
+
+// A token that keeps 10% of every transfer, like the SEP-41 tokens with a
+// transfer fee
+mod fee_token {
+    use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+
+    #[contracttype]
+    enum DataKey {
+        Balance(Address),
+    }
+
+    #[contract]
+    pub struct FeeToken;
+
+    #[contractimpl]
+    impl FeeToken {
+        pub fn mint(env: Env, to: Address, amount: i128) {
+            let balance = Self::balance(env.clone(), to.clone());
+            env.storage()
+                .persistent()
+                .set(&DataKey::Balance(to), &(balance + amount));
+        }
+
+        pub fn balance(env: Env, id: Address) -> i128 {
+            env.storage()
+                .persistent()
+                .get(&DataKey::Balance(id))
+                .unwrap_or(0)
+        }
+
+        pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+            from.require_auth();
+
+            let balance = Self::balance(env.clone(), from.clone());
+            assert!(balance >= amount, "insufficient balance");
+            env.storage()
+                .persistent()
+                .set(&DataKey::Balance(from), &(balance - amount));
+            Self::mint(env, to, amount - amount / 10);
+        }
+    }
+}
+
+#[test]
+fn test_bounty_with_transfer_fee() {
+    let (env, client, owner) = setup();
+    let assignee = Address::generate(&env);
+    let token_id = env.register(fee_token::FeeToken, ());
+    fee_token::FeeTokenClient::new(&env, &token_id).mint(&owner, &1_000);
+    let token = TokenClient::new(&env, &token_id);
+
+    // Only 270 of each 300 reach the contract
+    let paid = create_bounty(&env, &client, &owner, &token, 300);
+    let refunded = create_bounty(&env, &client, &owner, &token, 300);
+    assert_eq!(client.get_bounty(&owner, &paid.id).amount, 270);
+    assert_eq!(token.balance(&client.address), 540);
+
+    // Each payout only uses its own escrow
+    client.assign_todo(&owner, &owner, &paid.id, &assignee);
+    client.complete_todo(&owner, &owner, &paid.id);
+    assert_eq!(token.balance(&assignee), 243);
+    assert_eq!(token.balance(&client.address), 270);
+
+    client.delete_todo(&owner, &owner, &refunded.id);
+    assert_eq!(token.balance(&owner), 400 + 243);
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+fn create_many(env: &Env, client: &TodolistClient, owner: &Address, count: u32) {
+    let title = String::from_str(env, "Go home!!!");
+    let description = String::from_str(env, "From Garage to the hostel");
+
+    for _ in 0..count {
+        client.create_todo(
+            owner,
+            owner,
+            &title,
+            &description,
+            &meta(Priority::Medium, None, no_tags(env)),
+        );
+    }
+}
+
+#[test]
+fn test_get_todos_paginated() {
+    let (env, client, owner) = setup();
+    create_many(&env, &client, &owner, 7);
+    client.delete_todo(&owner, &owner, &3);
+
+    let page = client.get_todos(&owner, &0, &4);
+    assert_eq!(page.len(), 4);
+    assert_eq!(page.get(2).unwrap().id, 4);
+
+    let page = client.get_todos(&owner, &4, &4);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(1).unwrap().id, 7);
+
+    assert!(client.get_todos(&owner, &10, &4).is_empty());
+    assert_eq!(client.get_ids(&owner), vec![&env, 1, 2, 4, 5, 6, 7]);
+}
+
+#[test]
+fn test_migrate_legacy_list() {
+    let (env, client, owner) = setup();
+
+    let legacy = vec![
+        &env,
+        LegacyTodo {
+            id: 1,
+            title: String::from_str(&env, "Go home!!!"),
+            description: String::from_str(&env, "From Garage to the hostel"),
+            status: true,
+        },
+        LegacyTodo {
+            id: 2,
+            title: String::from_str(&env, "Sleep"),
+            description: String::from_str(&env, "Before the next class"),
+            status: false,
+        },
+    ];
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Todos(owner.clone()), &legacy);
+    });
+
+    assert_eq!(client.migrate(&owner), 2);
+    assert_eq!(client.migrate(&owner), 0);
+
+    let migrated = client.get_todos_enum(&owner);
+    assert_eq!(migrated.len(), 2);
+    assert_eq!(
+        migrated.get(0).unwrap(),
+        Todo {
+            id: 1,
+            title: String::from_str(&env, "Go home!!!"),
+            description: String::from_str(&env, "From Garage to the hostel"),
+            status: TodoStatus::Done,
+            priority: Priority::Medium,
+            due_ledger_timestamp: None,
+            tags: no_tags(&env),
+        }
+    );
+    assert_eq!(client.get_todo(&owner, &2).status, TodoStatus::Open);
+
+    // New todos continue after the migrated ids
+    let todo = client.create_todo(
+        &owner,
+        &owner,
+        &String::from_str(&env, "Wake up"),
+        &String::from_str(&env, "Class at 9"),
+        &meta(Priority::High, None, no_tags(&env)),
+    );
+    assert_eq!(todo.id, 3);
+}
+
+#[test]
+fn test_migrate_first_release_list() {
+    let (env, client, owner, admin) = setup_with_admin();
+
+    // The first release kept one list for everybody under the unit keys,
+    // and todo 2 had been deleted from it. Its ids are not kept
+    let legacy = vec![&env, sample_todo(&env, 1), sample_todo(&env, 3)];
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .set(&LegacyDataKey::Todos, &legacy);
+        env.storage()
+            .persistent()
+            .set(&LegacyDataKey::NextID, &4u32);
+    });
+
+    assert_eq!(client.migrate_legacy(&admin, &owner), 2);
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(client.migrate_legacy(&admin, &owner), 0);
+
+    assert_eq!(client.get_ids(&owner), vec![&env, 1, 2]);
+    assert_eq!(client.get_todo(&owner, &2).status, TodoStatus::Open);
+    env.as_contract(&client.address, || {
+        assert!(!env.storage().persistent().has(&LegacyDataKey::Todos));
+        assert!(!env.storage().persistent().has(&LegacyDataKey::NextID));
+    });
+
+    let todo = client.create_todo(
+        &owner,
+        &owner,
+        &String::from_str(&env, "Wake up"),
+        &String::from_str(&env, "Class at 9"),
+        &meta(Priority::High, None, no_tags(&env)),
+    );
+    assert_eq!(todo.id, 3);
+}
+
+#[test]
+fn test_migrate_into_a_list_with_todos() {
+    let (env, client, owner, admin) = setup_with_admin();
+    for title in ["Wake up", "Class at 9"] {
+        client.create_todo(
+            &owner,
+            &owner,
+            &String::from_str(&env, title),
+            &String::from_str(&env, ""),
+            &meta(Priority::High, None, no_tags(&env)),
+        );
+    }
+
+    let mut legacy = vec![&env, sample_todo(&env, 1), sample_todo(&env, 2)];
+    let mut done = sample_todo(&env, 2);
+    done.status = true;
+    legacy.set(1, done);
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .set(&LegacyDataKey::Todos, &legacy);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Todos(owner.clone()), &legacy);
+    });
+
+    // Both old lists use ids 1 and 2, which the owner's own todos hold
+    assert_eq!(client.migrate_legacy(&admin, &owner), 2);
+    assert_eq!(client.migrate(&owner), 2);
+
+    assert_eq!(client.get_ids(&owner), vec![&env, 1, 2, 3, 4, 5, 6]);
+    assert_eq!(
+        client.get_todo(&owner, &1).title,
+        String::from_str(&env, "Wake up")
+    );
+    assert_eq!(
+        client.get_todo(&owner, &2).title,
+        String::from_str(&env, "Class at 9")
+    );
+    assert_eq!(client.get_todo(&owner, &3).status, TodoStatus::Open);
+    assert_eq!(client.get_todo(&owner, &4).status, TodoStatus::Done);
+    assert_eq!(client.get_todo(&owner, &6).status, TodoStatus::Done);
+    assert_eq!(client.get_id_enum(&owner), 7);
+}
+
+#[test]
+fn test_migrate_first_release_list_is_admin_only() {
+    let (env, client, owner, admin) = setup_with_admin();
+
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .set(&LegacyDataKey::Todos, &vec![&env, sample_todo(&env, 1)]);
+    });
+
+    // The owner is not the admin, so cannot claim the shared list
+    assert_eq!(
+        client.try_migrate_legacy(&owner, &owner),
+        Err(Ok(TodoError::Unauthorized))
+    );
+    assert_eq!(client.get_ids(&owner).len(), 0);
+
+    // Nor sign for the admin
+    env.mock_auths(&[MockAuth {
+        address: &owner,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "migrate_legacy",
+            args: (admin.clone(), owner.clone()).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(client.try_migrate_legacy(&admin, &owner).is_err());
+    assert_eq!(client.get_ids(&owner).len(), 0);
+}
+
+// The first release as it was deployed: no constructor, no admin and one
+// list shared by everybody under the unit keys
+mod first_release {
+    use crate::todo_list::{LegacyDataKey, LegacyTodo};
+    use soroban_sdk::{contract, contractimpl, Env, String, Vec};
+
+    #[contract]
+    pub struct FirstRelease;
+
+    #[contractimpl]
+    impl FirstRelease {
+        pub fn create_todo(env: Env, title: String, description: String) -> u32 {
+            let storage = env.storage().persistent();
+            let mut todos: Vec<LegacyTodo> =
+                storage.get(&LegacyDataKey::Todos).unwrap_or(Vec::new(&env));
+            let id: u32 = storage.get(&LegacyDataKey::NextID).unwrap_or(1);
+
+            todos.push_back(LegacyTodo {
+                id,
+                title,
+                description,
+                status: false,
+            });
+            storage.set(&LegacyDataKey::Todos, &todos);
+            storage.set(&LegacyDataKey::NextID, &(id + 1));
+            id
+        }
+    }
+}
+
+// A wasm module with nothing in it but the interface version the host
+// checks on upload, for exercising `upgrade` itself
+fn empty_wasm(env: &Env) -> Bytes {
+    let meta = ScEnvMetaEntry::ScEnvMetaKindInterfaceVersion(ScEnvMetaEntryInterfaceVersion {
+        protocol: env.ledger().protocol_version(),
+        pre_release: 0,
+    })
+    .to_xdr(Limits::none())
+    .unwrap();
+    let name = b"contractenvmetav0";
+
+    let mut wasm = std::vec![0x00, b'a', b's', b'm', 1, 0, 0, 0];
+    wasm.push(0);
+    wasm.push((1 + name.len() + meta.len()) as u8);
+    wasm.push(name.len() as u8);
+    wasm.extend_from_slice(name);
+    wasm.extend_from_slice(&meta);
+
+    Bytes::from_slice(env, &wasm)
+}
+
+#[test]
+fn test_migrate_after_upgrading_the_first_release() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(first_release::FirstRelease, ());
+    let first = first_release::FirstReleaseClient::new(&env, &contract_id);
+    first.create_todo(
+        &String::from_str(&env, "Go home!!!"),
+        &String::from_str(&env, "From Garage to the hostel"),
+    );
+    first.create_todo(
+        &String::from_str(&env, "Sleep"),
+        &String::from_str(&env, "Before the next class"),
+    );
+
+    // Tests can only swap in native code by registering it again, which
+    // runs the constructor; an upgrade on chain does not, so undo that
+    env.register_at(&contract_id, Todolist, (Address::generate(&env),));
+    env.as_contract(&contract_id, || {
+        env.storage().instance().remove(&DataKey::Admin);
+    });
+    let client = TodolistClient::new(&env, &contract_id);
+
+    // Whoever migrates first becomes the admin
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    assert_eq!(client.migrate_legacy(&admin, &owner), 2);
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(client.get_ids(&owner), vec![&env, 1, 2]);
+    assert_eq!(
+        client.get_todo(&owner, &2).title,
+        String::from_str(&env, "Sleep")
+    );
+
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_migrate_legacy(&stranger, &stranger),
+        Err(Ok(TodoError::Unauthorized))
+    );
+    let hash = env.deployer().upload_contract_wasm(empty_wasm(&env));
+    assert_eq!(
+        client.try_upgrade(&stranger, &hash),
+        Err(Ok(TodoError::Unauthorized))
+    );
+
+    client.upgrade(&admin, &hash);
+    // The new code has no functions at all
+    assert!(client.try_get_ids(&owner).is_err());
+}
+
+fn sample_todo(env: &Env, id: u32) -> LegacyTodo {
+    LegacyTodo {
+        id,
+        title: String::from_str(env, "Go home!!!"),
+        description: String::from_str(env, "From Garage to the hostel"),
+        status: false,
+    }
+}
+
+// CPU instructions and memory bytes of completing one todo in a list of
+// `count`, the way `complete_todo` did it with a single `Vec<Todo>`
+fn legacy_complete_cost(count: u32) -> (u64, u64) {
+    let (env, client, owner) = setup();
+    let key = DataKey::Todos(owner.clone());
+
+    let mut todos = Vec::new(&env);
+    for id in 1..=count {
+        todos.push_back(sample_todo(&env, id));
+    }
+    env.as_contract(&client.address, || {
+        env.storage().persistent().set(&key, &todos);
+    });
+
+    env.cost_estimate().budget().reset_default();
+    env.as_contract(&client.address, || {
+        let mut todos: Vec<LegacyTodo> = env.storage().persistent().get(&key).unwrap();
+        for i in 0..todos.len() {
+            let mut todo = todos.get(i).unwrap();
+            if todo.id == 1 {
+                todo.status = !todo.status;
+                todos.set(i, todo);
+                env.storage().persistent().set(&key, &todos);
+                break;
+            }
+        }
+    });
+
+    let budget = env.cost_estimate().budget();
+    (budget.cpu_instruction_cost(), budget.memory_bytes_cost())
+}
+
+// Same measurement through the contract with one entry per todo
+fn complete_cost(count: u32) -> (u64, u64) {
+    let (env, client, owner) = setup();
+    create_many(&env, &client, &owner, count);
+
+    env.cost_estimate().budget().reset_default();
+    client.complete_todo(&owner, &owner, &1);
+
+    let budget = env.cost_estimate().budget();
+    (budget.cpu_instruction_cost(), budget.memory_bytes_cost())
+}
+
+#[test]
+fn test_budget_before_and_after() {
+    let (legacy_small_cpu, legacy_small_mem) = legacy_complete_cost(10);
+    let (legacy_large_cpu, legacy_large_mem) = legacy_complete_cost(200);
+    let (small_cpu, small_mem) = complete_cost(10);
+    let (large_cpu, large_mem) = complete_cost(200);
+
+    assert!(small_cpu < legacy_small_cpu);
+    assert!(small_mem < legacy_small_mem);
+
+    // The test host still charges a little more as the contract holds more
+    // entries, but nowhere near decoding and re-encoding the whole list
+    assert!(large_cpu * 4 < legacy_large_cpu);
+    assert!(large_mem * 2 < legacy_large_mem);
 }
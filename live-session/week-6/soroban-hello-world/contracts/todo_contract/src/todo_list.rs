@@ -1,132 +1,506 @@
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Env, String, Symbol, Vec};
+use soroban_sdk::{contractimpl, contracttype, Address, BytesN, Env, String, Symbol, Vec};
+
+use crate::bounty;
+use crate::error::TodoError;
+use crate::events;
+pub use crate::{Todolist, TodolistArgs, TodolistClient};
+
+const MAX_TITLE_LEN: u32 = 64;
+const MAX_DESCRIPTION_LEN: u32 = 256;
+const MAX_TAGS: u32 = 5;
+
+#[contracttype]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+#[contracttype]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TodoStatus {
+    Open,
+    InProgress,
+    Done,
+    Archived,
+}
 
 #[contracttype]
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Todo {
+    pub id: u32,
+    pub title: String,
+    pub description: String,
+    pub status: TodoStatus,
+    pub priority: Priority,
+    /// Ledger timestamp (seconds) the todo should be done by, if any.
+    pub due_ledger_timestamp: Option<u64>,
+    pub tags: Vec<Symbol>,
+}
+
+/// The fields of a todo that can be set on creation or changed later
+/// with `update_meta`.
+#[contracttype]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TodoMeta {
+    pub priority: Priority,
+    pub due_ledger_timestamp: Option<u64>,
+    pub tags: Vec<Symbol>,
+}
+
+/// The shape a todo had when the whole list lived in one entry, under
+/// `LegacyDataKey::Todos` or `DataKey::Todos`, only used to read those
+/// lists in `migrate` and `migrate_legacy`.
+#[contracttype]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LegacyTodo {
     pub id: u32,
     pub title: String,
     pub description: String,
     pub status: bool,
 }
 
+/// The keys of the first release, before lists were kept per owner: one
+/// shared `Vec<LegacyTodo>` under `Todos` and the next id under `NextID`.
+/// Still declared so a contract upgraded from that release can read them.
 #[contracttype]
-enum DataKey {
+pub enum LegacyDataKey {
     Todos,
     NextID,
 }
 
-const TODOS: Symbol = symbol_short!("TOD0S");
-
-const NEXT_ID: Symbol = symbol_short!("NEXT_ID");
-
-#[contract]
-pub struct Todolist;
+#[contracttype]
+pub(crate) enum DataKey {
+    // Whole list in one entry, only read by `migrate`
+    Todos(Address),
+    Todo(Address, u32),
+    TodoIds(Address),
+    NextID(Address),
+    Editors(Address),
+    Bounty(Address, u32),
+    Admin,
+}
 
 #[contractimpl]
 impl Todolist {
-    pub fn create_todo(env: Env, title: String, description: String) -> Todo {
-        let mut todos = Self::get_todos_enum(&env);
+    /// `admin` is the only account that can upgrade the contract and move
+    /// the first release's shared list with `migrate_legacy`.
+    pub fn __constructor(env: Env, admin: Address) {
+        env.storage().instance().set(&DataKey::Admin, &admin);
+    }
+
+    /// Swaps the contract's code for the wasm at `new_wasm_hash`, keeping
+    /// its storage. Only the admin can upgrade.
+    pub fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) -> Result<(), TodoError> {
+        Self::require_admin(&env, &caller)?;
+
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        Ok(())
+    }
 
-        let mut current_id = Self::get_id_enum(&env);
+    pub fn create_todo(
+        env: Env,
+        caller: Address,
+        owner: Address,
+        title: String,
+        description: String,
+        meta: TodoMeta,
+    ) -> Result<Todo, TodoError> {
+        Self::authorize(&env, &caller, &owner)?;
+        Self::validate(&title, &description)?;
+        Self::validate_tags(&meta.tags)?;
+
+        let mut current_id = Self::get_id_enum(&env, owner.clone());
 
         let todo = Todo {
             id: current_id,
             title,
             description,
-            status: false,
+            status: TodoStatus::Open,
+            priority: meta.priority,
+            due_ledger_timestamp: meta.due_ledger_timestamp,
+            tags: meta.tags,
         };
 
-        todos.push_back(todo.clone());
+        Self::save_todo(&env, &owner, &todo);
+        events::todo_created_event(&env, owner.clone(), &todo);
 
-        env.storage().persistent().set(&DataKey::Todos, &todos);
+        let mut ids = Self::get_ids(&env, owner.clone());
+        ids.push_back(current_id);
+        env.storage()
+            .persistent()
+            .set(&DataKey::TodoIds(owner.clone()), &ids);
 
         current_id += 1;
 
         env.storage()
             .persistent()
-            .set(&DataKey::NextID, &current_id);
+            .set(&DataKey::NextID(owner), &current_id);
 
-        todo
+        Ok(todo)
     }
 
-    pub fn update_todo(env: Env, id: u32, title: String, description: String) -> bool {
-        let mut todos = Self::get_todos_enum(&env);
-        for i in 0..todos.len() {
-            let mut updated = todos.get(i).unwrap();
-            if updated.id == id {
-                updated.title = title;
-                updated.description = description;
-                todos.set(i, updated);
-                env.storage().persistent().set(&DataKey::Todos, &todos);
-                return true;
+    pub fn update_todo(
+        env: Env,
+        caller: Address,
+        owner: Address,
+        id: u32,
+        title: String,
+        description: String,
+    ) -> Result<(), TodoError> {
+        Self::authorize(&env, &caller, &owner)?;
+        Self::validate(&title, &description)?;
+
+        let mut todo = Self::load_todo(&env, &owner, id)?;
+        todo.title = title;
+        todo.description = description;
+        Self::save_todo(&env, &owner, &todo);
+
+        events::todo_updated_event(&env, owner, &todo);
+        Ok(())
+    }
+
+    pub fn complete_todo(
+        env: Env,
+        caller: Address,
+        owner: Address,
+        id: u32,
+    ) -> Result<(), TodoError> {
+        Self::set_status(env, caller, owner, id, TodoStatus::Done)
+    }
+
+    /// Moves a todo along `Open -> InProgress -> Done -> Archived`. A todo
+    /// can go back to `Open` from `InProgress` or `Done`, and can be
+    /// archived from any state, but nothing leaves `Archived`.
+    pub fn set_status(
+        env: Env,
+        caller: Address,
+        owner: Address,
+        id: u32,
+        status: TodoStatus,
+    ) -> Result<(), TodoError> {
+        Self::authorize(&env, &caller, &owner)?;
+
+        let mut todo = Self::load_todo(&env, &owner, id)?;
+        let from = todo.status;
+
+        let allowed = matches!(
+            (from, status),
+            (TodoStatus::Open, TodoStatus::InProgress)
+                | (TodoStatus::Open, TodoStatus::Done)
+                | (TodoStatus::InProgress, TodoStatus::Done)
+                | (TodoStatus::InProgress, TodoStatus::Open)
+                | (TodoStatus::Done, TodoStatus::Open)
+                | (TodoStatus::Open, TodoStatus::Archived)
+                | (TodoStatus::InProgress, TodoStatus::Archived)
+                | (TodoStatus::Done, TodoStatus::Archived)
+        );
+        if !allowed {
+            if from == TodoStatus::Done && status == TodoStatus::Done {
+                return Err(TodoError::AlreadyCompleted);
             }
+            return Err(TodoError::InvalidTransition);
         }
-        false
+
+        match status {
+            TodoStatus::Done => bounty::release(&env, &caller, &owner, id)?,
+            TodoStatus::Archived if bounty::is_pending(&env, &owner, id) => {
+                return Err(TodoError::BountyPending)
+            }
+            _ => (),
+        }
+
+        todo.status = status;
+        Self::save_todo(&env, &owner, &todo);
+
+        events::todo_status_event(&env, owner, id, from, status);
+        Ok(())
     }
 
-    pub fn complete_todo(env: Env, id: u32) -> bool {
-        let mut todos = Self::get_todos_enum(&env);
+    pub fn update_meta(
+        env: Env,
+        caller: Address,
+        owner: Address,
+        id: u32,
+        meta: TodoMeta,
+    ) -> Result<(), TodoError> {
+        Self::authorize(&env, &caller, &owner)?;
+        Self::validate_tags(&meta.tags)?;
 
-        for i in 0..todos.len() {
-            if let Some(mut todo) = todos.get(i) {
-                if todo.id == id {
-                    todo.status = !todo.status;
-                    todos.set(i, todo);
-                    env.storage().persistent().set(&DataKey::Todos, &todos);
-                    return true;
-                }
-            }
+        let mut todo = Self::load_todo(&env, &owner, id)?;
+        todo.priority = meta.priority;
+        todo.due_ledger_timestamp = meta.due_ledger_timestamp;
+        todo.tags = meta.tags;
+        Self::save_todo(&env, &owner, &todo);
+
+        events::todo_updated_event(&env, owner, &todo);
+        Ok(())
+    }
+
+    pub fn delete_todo(
+        env: Env,
+        caller: Address,
+        owner: Address,
+        id: u32,
+    ) -> Result<(), TodoError> {
+        Self::authorize(&env, &caller, &owner)?;
+
+        let mut ids = Self::get_ids(&env, owner.clone());
+        let i = ids.first_index_of(id).ok_or(TodoError::NotFound)?;
+
+        ids.remove(i);
+        env.storage()
+            .persistent()
+            .set(&DataKey::TodoIds(owner.clone()), &ids);
+        bounty::refund(&env, &owner, id);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Todo(owner.clone(), id));
+
+        events::todo_deleted_event(&env, owner, id);
+        Ok(())
+    }
+
+    pub fn update_todo2(
+        env: Env,
+        caller: Address,
+        owner: Address,
+        id: u32,
+        title: String,
+        description: String,
+    ) -> Result<(), TodoError> {
+        Self::update_todo(env, caller, owner, id, title, description)
+    }
+
+    /// Moves a list stored the old way, as one `Vec<Todo>` under
+    /// `DataKey::Todos`, into one entry per todo, numbered on from the
+    /// owner's next id. Returns how many todos were moved, 0 once the list
+    /// has already been migrated.
+    pub fn migrate(env: Env, owner: Address) -> u32 {
+        owner.require_auth();
+
+        let key = DataKey::Todos(owner.clone());
+        let legacy: Vec<LegacyTodo> = match env.storage().persistent().get(&key) {
+            Some(todos) => todos,
+            None => return 0,
+        };
+
+        Self::import(&env, &owner, &legacy);
+        env.storage().persistent().remove(&key);
+
+        legacy.len()
+    }
+
+    /// Moves the first release's shared list, under the unit
+    /// `LegacyDataKey::Todos`, into `owner`'s list. That list has no owner
+    /// recorded, so only the admin can say whose it is. The todos are
+    /// numbered on from `owner`'s next id. Returns how many todos were
+    /// moved, 0 once it has already been migrated.
+    ///
+    /// A contract carried over from the first release never ran
+    /// `__constructor`, so it has no admin yet and the caller of this
+    /// migration becomes the admin. Call it in the same transaction that
+    /// installs this code, so nobody else can claim the role first.
+    pub fn migrate_legacy(env: Env, caller: Address, owner: Address) -> Result<u32, TodoError> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            Self::require_admin(&env, &caller)?;
+        } else {
+            caller.require_auth();
+            env.storage().instance().set(&DataKey::Admin, &caller);
         }
-        false
+
+        let legacy: Vec<LegacyTodo> = match env.storage().persistent().get(&LegacyDataKey::Todos) {
+            Some(todos) => todos,
+            None => return Ok(0),
+        };
+        Self::import(&env, &owner, &legacy);
+        env.storage().persistent().remove(&LegacyDataKey::Todos);
+        env.storage().persistent().remove(&LegacyDataKey::NextID);
+
+        Ok(legacy.len())
     }
 
-    pub fn delete_todo(env: Env, id: u32) -> bool {
-        let mut todos = Self::get_todos_enum(&env);
+    /// Lets `editor` create, update, complete and delete todos on the
+    /// owner's list. Only the owner can share or unshare.
+    pub fn share_list(env: Env, owner: Address, editor: Address) {
+        owner.require_auth();
 
-        if let Some(todo) = todos.iter().position(|i| i.id == id) {
-            todos.remove(todo as u32);
-            env.storage().persistent().set(&DataKey::Todos, &todos);
-            return true;
+        let mut editors = Self::get_editors(&env, owner.clone());
+        if !editors.contains(&editor) {
+            editors.push_back(editor);
+            env.storage()
+                .persistent()
+                .set(&DataKey::Editors(owner), &editors);
         }
+    }
+
+    pub fn unshare_list(env: Env, owner: Address, editor: Address) {
+        owner.require_auth();
+
+        let mut editors = Self::get_editors(&env, owner.clone());
+        if let Some(i) = editors.first_index_of(&editor) {
+            editors.remove(i);
+            env.storage()
+                .persistent()
+                .set(&DataKey::Editors(owner), &editors);
+        }
+    }
+
+    pub fn get_editors(env: &Env, owner: Address) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Editors(owner))
+            .unwrap_or(Vec::new(env))
+    }
 
-        false
+    pub fn get_todo(env: &Env, owner: Address, id: u32) -> Result<Todo, TodoError> {
+        Self::load_todo(env, &owner, id)
     }
 
-    pub fn update_todo2(env: Env, id: u32, title: String, description: String) -> bool {
-        let mut todos = Self::get_todos_enum(&env);
+    /// Returns at most `limit` todos, skipping the first `offset`, in the
+    /// order they were created.
+    pub fn get_todos(env: &Env, owner: Address, offset: u32, limit: u32) -> Vec<Todo> {
+        let ids = Self::get_ids(env, owner.clone());
+        let mut todos = Vec::new(env);
 
-        for i in 0..todos.len() {
-            if let Some(mut todo) = todos.get(i) {
-                if todo.id == id {
-                    todo.title = title;
-                    todo.description = description;
-                    todos.set(i, todo);
-                    env.storage().persistent().set(&DataKey::Todos, &todos);
+        let end = offset.saturating_add(limit).min(ids.len());
+        for i in offset..end {
+            let id = ids.get(i).unwrap();
+            if let Ok(todo) = Self::load_todo(env, &owner, id) {
+                todos.push_back(todo);
+            }
+        }
+        todos
+    }
 
-                    return true;
+    pub fn get_todos_enum(env: &Env, owner: Address) -> Vec<Todo> {
+        let count = Self::get_ids(env, owner.clone()).len();
+        Self::get_todos(env, owner, 0, count)
+    }
+
+    /// Todos with a due timestamp before the current ledger's that are
+    /// still `Open` or `InProgress`.
+    pub fn get_overdue(env: &Env, owner: Address) -> Vec<Todo> {
+        let now = env.ledger().timestamp();
+        let mut overdue = Vec::new(env);
+
+        for todo in Self::get_todos_enum(env, owner).iter() {
+            let pending = matches!(todo.status, TodoStatus::Open | TodoStatus::InProgress);
+            if let Some(due) = todo.due_ledger_timestamp {
+                if pending && due < now {
+                    overdue.push_back(todo);
                 }
             }
         }
-        false
+        overdue
     }
 
-    pub fn get_todos(env: &Env) -> Vec<Todo> {
+    pub fn get_ids(env: &Env, owner: Address) -> Vec<u32> {
         env.storage()
             .persistent()
-            .get(&TODOS)
+            .get(&DataKey::TodoIds(owner))
             .unwrap_or(Vec::new(env))
     }
 
-    pub fn get_todos_enum(env: &Env) -> Vec<Todo> {
+    pub fn get_id_enum(env: &Env, owner: Address) -> u32 {
         env.storage()
             .persistent()
-            .get(&DataKey::Todos)
-            .unwrap_or(Vec::new(env))
+            .get(&DataKey::NextID(owner))
+            .unwrap_or(1)
     }
-    pub fn get_id_enum(env: &Env) -> u32 {
+
+    // Appends `legacy` to the owner's list in order, with a `created` event
+    // for each. Each todo gets the owner's next id rather than its old one,
+    // which may already be taken by a todo the owner created since
+    fn import(env: &Env, owner: &Address, legacy: &Vec<LegacyTodo>) {
+        let mut ids = Self::get_ids(env, owner.clone());
+        let mut next_id = Self::get_id_enum(env, owner.clone());
+
+        for todo in legacy.iter() {
+            let id = next_id;
+            next_id += 1;
+            ids.push_back(id);
+
+            let status = if todo.status {
+                TodoStatus::Done
+            } else {
+                TodoStatus::Open
+            };
+            let todo = Todo {
+                id,
+                title: todo.title,
+                description: todo.description,
+                status,
+                priority: Priority::Medium,
+                due_ledger_timestamp: None,
+                tags: Vec::new(env),
+            };
+            Self::save_todo(env, owner, &todo);
+            events::todo_created_event(env, owner.clone(), &todo);
+        }
+
         env.storage()
             .persistent()
-            .get(&DataKey::NextID)
-            .unwrap_or(1)
+            .set(&DataKey::TodoIds(owner.clone()), &ids);
+        env.storage()
+            .persistent()
+            .set(&DataKey::NextID(owner.clone()), &next_id);
+    }
+
+    // Every read of a single todo goes through here, so there is only one
+    // place that knows which key a todo lives under
+    fn load_todo(env: &Env, owner: &Address, id: u32) -> Result<Todo, TodoError> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Todo(owner.clone(), id))
+            .ok_or(TodoError::NotFound)
+    }
+
+    fn save_todo(env: &Env, owner: &Address, todo: &Todo) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Todo(owner.clone(), todo.id), todo);
+    }
+
+    fn require_admin(env: &Env, caller: &Address) -> Result<(), TodoError> {
+        caller.require_auth();
+
+        let admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+        if admin.as_ref() != Some(caller) {
+            return Err(TodoError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    // The caller signs for the call and must own the list or have been
+    // added as an editor by the owner
+    fn authorize(env: &Env, caller: &Address, owner: &Address) -> Result<(), TodoError> {
+        caller.require_auth();
+
+        if caller != owner && !Self::get_editors(env, owner.clone()).contains(caller) {
+            return Err(TodoError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    fn validate(title: &String, description: &String) -> Result<(), TodoError> {
+        if title.is_empty() {
+            return Err(TodoError::EmptyTitle);
+        }
+        if title.len() > MAX_TITLE_LEN {
+            return Err(TodoError::TitleTooLong);
+        }
+        if description.len() > MAX_DESCRIPTION_LEN {
+            return Err(TodoError::DescriptionTooLong);
+        }
+        Ok(())
+    }
+
+    fn validate_tags(tags: &Vec<Symbol>) -> Result<(), TodoError> {
+        if tags.len() > MAX_TAGS {
+            return Err(TodoError::TooManyTags);
+        }
+        Ok(())
     }
 }
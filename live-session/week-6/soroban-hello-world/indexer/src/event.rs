@@ -0,0 +1,184 @@
+use serde::Deserialize;
+use soroban_sdk::{
+    testutils::Events,
+    xdr::{Limits, ReadXdr, ScVal, WriteXdr},
+    Address, Env, Symbol, TryFromVal, Val, Vec,
+};
+use todo_contract::todo_list::{Priority, Todo, TodoStatus};
+
+/// A todo as it is stored in the mirror, using plain Rust types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TodoRow {
+    pub owner: String,
+    pub id: u32,
+    pub title: String,
+    pub description: String,
+    pub status: String,
+    pub priority: String,
+    pub due_ledger_timestamp: Option<u64>,
+    pub tags: std::vec::Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TodoEvent {
+    Created(TodoRow),
+    Updated(TodoRow),
+    Status {
+        owner: String,
+        id: u32,
+        from: String,
+        to: String,
+    },
+    Deleted {
+        owner: String,
+        id: u32,
+    },
+}
+
+pub fn status_name(status: TodoStatus) -> String {
+    format!("{:?}", status)
+}
+
+pub fn priority_name(priority: Priority) -> String {
+    format!("{:?}", priority)
+}
+
+fn row(owner: String, todo: Todo) -> TodoRow {
+    TodoRow {
+        owner,
+        id: todo.id,
+        title: todo.title.to_string(),
+        description: todo.description.to_string(),
+        status: status_name(todo.status),
+        priority: priority_name(todo.priority),
+        due_ledger_timestamp: todo.due_ledger_timestamp,
+        tags: todo.tags.iter().map(|tag| tag.to_string()).collect(),
+    }
+}
+
+/// Turns one contract event into a `TodoEvent`. Returns `None` for events
+/// this indexer does not know about, e.g. from another contract.
+pub fn decode(env: &Env, topics: &Vec<Val>, data: Val) -> Option<TodoEvent> {
+    if topics.len() != 3 {
+        return None;
+    }
+
+    let name = Symbol::try_from_val(env, &topics.get(0)?).ok()?;
+    let owner = Address::try_from_val(env, &topics.get(1)?).ok()?;
+    let owner = owner.to_string().to_string();
+    let id = u32::try_from_val(env, &topics.get(2)?).ok()?;
+
+    let event = match name.to_string().as_str() {
+        "created" => TodoEvent::Created(row(owner, Todo::try_from_val(env, &data).ok()?)),
+        "updated" => TodoEvent::Updated(row(owner, Todo::try_from_val(env, &data).ok()?)),
+        "status" => {
+            let (from, to) = <(TodoStatus, TodoStatus)>::try_from_val(env, &data).ok()?;
+            TodoEvent::Status {
+                owner,
+                id,
+                from: status_name(from),
+                to: status_name(to),
+            }
+        }
+        "deleted" => TodoEvent::Deleted { owner, id },
+        _ => return None,
+    };
+    Some(event)
+}
+
+/// Decodes the events published by `contract` during the last invocation
+/// in a test `Env`.
+pub fn from_env(env: &Env, contract: &Address) -> std::vec::Vec<TodoEvent> {
+    env.events()
+        .all()
+        .iter()
+        .filter(|(address, _, _)| address == contract)
+        .filter_map(|(_, topics, data)| decode(env, &topics, data))
+        .collect()
+}
+
+/// One event as returned by the RPC `getEvents` method (and
+/// `stellar events --output json`): topics and value are base64 XDR
+/// `ScVal`s.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcEvent {
+    pub contract_id: String,
+    pub topic: std::vec::Vec<String>,
+    pub value: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RpcDump {
+    Response { result: RpcEvents },
+    Events(RpcEvents),
+    List(std::vec::Vec<RpcEvent>),
+}
+
+#[derive(Deserialize)]
+struct RpcEvents {
+    events: std::vec::Vec<RpcEvent>,
+}
+
+fn scval_from_base64(env: &Env, b64: &str) -> Result<Val, String> {
+    let scval = ScVal::from_xdr_base64(b64, Limits::none()).map_err(|e| e.to_string())?;
+    Val::try_from_val(env, &scval).map_err(|e| format!("{:?}", e))
+}
+
+/// Reads a JSON dump of contract events from a local node, either the raw
+/// `getEvents` response, its `result`, or a bare list of events. Only
+/// events from `contract_id` are kept when one is given.
+pub fn from_rpc_json(
+    env: &Env,
+    json: &str,
+    contract_id: Option<&str>,
+) -> Result<std::vec::Vec<TodoEvent>, String> {
+    let dump: RpcDump = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let events = match dump {
+        RpcDump::Response { result } => result.events,
+        RpcDump::Events(events) => events.events,
+        RpcDump::List(events) => events,
+    };
+
+    let mut decoded = std::vec::Vec::new();
+    for event in events {
+        if contract_id.is_some_and(|id| id != event.contract_id) {
+            continue;
+        }
+
+        let mut topics = Vec::new(env);
+        for topic in &event.topic {
+            topics.push_back(scval_from_base64(env, topic)?);
+        }
+        let data = scval_from_base64(env, &event.value)?;
+
+        if let Some(event) = decode(env, &topics, data) {
+            decoded.push(event);
+        }
+    }
+    Ok(decoded)
+}
+
+/// The reverse of `from_rpc_json` for the events of the last invocation in
+/// a test `Env`, handy for producing fixtures.
+pub fn to_rpc_events(env: &Env) -> std::vec::Vec<serde_json::Value> {
+    let to_base64 = |val: &Val| {
+        ScVal::try_from_val(env, val)
+            .unwrap()
+            .to_xdr_base64(Limits::none())
+            .unwrap()
+    };
+
+    env.events()
+        .all()
+        .iter()
+        .map(|(contract, topics, data)| {
+            serde_json::json!({
+                "contractId": contract.to_string().to_string(),
+                "topic": topics.iter().map(|t| to_base64(&t)).collect::<std::vec::Vec<_>>(),
+                "value": to_base64(&data),
+            })
+        })
+        .collect()
+}
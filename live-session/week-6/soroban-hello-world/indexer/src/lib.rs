@@ -0,0 +1,3 @@
+pub mod event;
+pub mod mirror;
+mod test;
@@ -0,0 +1,29 @@
+use std::env;
+use std::fs;
+
+use todo_indexer::event::from_rpc_json;
+use todo_indexer::mirror::Mirror;
+
+fn main() -> Result<(), String> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() < 2 {
+        return Err("usage: todo-indexer <events.json> <mirror.sqlite> [contract_id]".to_string());
+    }
+
+    let json = fs::read_to_string(&args[0]).map_err(|e| e.to_string())?;
+    let contract_id = args.get(2).map(String::as_str);
+
+    let env = soroban_sdk::Env::default();
+    let events = from_rpc_json(&env, &json, contract_id)?;
+
+    let mirror = Mirror::open(&args[1]).map_err(|e| e.to_string())?;
+    mirror.apply_all(&events).map_err(|e| e.to_string())?;
+
+    println!(
+        "Applied {} events, {} todos in {}",
+        events.len(),
+        mirror.count().map_err(|e| e.to_string())?,
+        args[1]
+    );
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::event::{TodoEvent, TodoRow};
+
+/// A local SQLite copy of every todo list, rebuilt from contract events.
+pub struct Mirror {
+    conn: Connection,
+}
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS todos (
+        owner TEXT NOT NULL,
+        id INTEGER NOT NULL,
+        title TEXT NOT NULL,
+        description TEXT NOT NULL,
+        status TEXT NOT NULL,
+        priority TEXT NOT NULL,
+        due_ledger_timestamp INTEGER,
+        tags TEXT NOT NULL,
+        PRIMARY KEY (owner, id)
+    );
+";
+
+const COLUMNS: &str = "owner, id, title, description, status, priority, due_ledger_timestamp, tags";
+
+fn read_row(row: &Row) -> rusqlite::Result<TodoRow> {
+    let tags: String = row.get(7)?;
+    let due: Option<i64> = row.get(6)?;
+
+    Ok(TodoRow {
+        owner: row.get(0)?,
+        id: row.get(1)?,
+        title: row.get(2)?,
+        description: row.get(3)?,
+        status: row.get(4)?,
+        priority: row.get(5)?,
+        due_ledger_timestamp: due.map(|due| due as u64),
+        tags: tags
+            .split(',')
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string)
+            .collect(),
+    })
+}
+
+impl Mirror {
+    pub fn open(path: &str) -> rusqlite::Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn in_memory() -> rusqlite::Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> rusqlite::Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    pub fn apply(&self, event: &TodoEvent) -> rusqlite::Result<()> {
+        match event {
+            TodoEvent::Created(todo) | TodoEvent::Updated(todo) => {
+                self.conn.execute(
+                    &format!("INSERT OR REPLACE INTO todos ({COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"),
+                    params![
+                        todo.owner,
+                        todo.id,
+                        todo.title,
+                        todo.description,
+                        todo.status,
+                        todo.priority,
+                        todo.due_ledger_timestamp.map(|due| due as i64),
+                        todo.tags.join(","),
+                    ],
+                )?;
+            }
+            TodoEvent::Status { owner, id, to, .. } => {
+                self.conn.execute(
+                    "UPDATE todos SET status = ?1 WHERE owner = ?2 AND id = ?3",
+                    params![to, owner, id],
+                )?;
+            }
+            TodoEvent::Deleted { owner, id } => {
+                self.conn.execute(
+                    "DELETE FROM todos WHERE owner = ?1 AND id = ?2",
+                    params![owner, id],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn apply_all(&self, events: &[TodoEvent]) -> rusqlite::Result<()> {
+        for event in events {
+            self.apply(event)?;
+        }
+        Ok(())
+    }
+
+    pub fn todo(&self, owner: &str, id: u32) -> rusqlite::Result<Option<TodoRow>> {
+        self.conn
+            .query_row(
+                &format!("SELECT {COLUMNS} FROM todos WHERE owner = ?1 AND id = ?2"),
+                params![owner, id],
+                read_row,
+            )
+            .optional()
+    }
+
+    pub fn todos(&self, owner: &str) -> rusqlite::Result<Vec<TodoRow>> {
+        self.query(
+            &format!("SELECT {COLUMNS} FROM todos WHERE owner = ?1 ORDER BY id"),
+            params![owner],
+        )
+    }
+
+    pub fn with_status(&self, owner: &str, status: &str) -> rusqlite::Result<Vec<TodoRow>> {
+        self.query(
+            &format!("SELECT {COLUMNS} FROM todos WHERE owner = ?1 AND status = ?2 ORDER BY id"),
+            params![owner, status],
+        )
+    }
+
+    /// Same rule as the contract's `get_overdue`.
+    pub fn overdue(&self, owner: &str, now: u64) -> rusqlite::Result<Vec<TodoRow>> {
+        self.query(
+            &format!(
+                "SELECT {COLUMNS} FROM todos
+                 WHERE owner = ?1
+                   AND status IN ('Open', 'InProgress')
+                   AND due_ledger_timestamp IS NOT NULL
+                   AND due_ledger_timestamp < ?2
+                 ORDER BY id"
+            ),
+            params![owner, now as i64],
+        )
+    }
+
+    pub fn count(&self) -> rusqlite::Result<u32> {
+        self.conn
+            .query_row("SELECT COUNT(*) FROM todos", [], |row| row.get(0))
+    }
+
+    fn query(&self, sql: &str, params: impl rusqlite::Params) -> rusqlite::Result<Vec<TodoRow>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map(params, read_row)?;
+        rows.collect()
+    }
+}
@@ -0,0 +1,249 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, String, Vec,
+};
+use todo_contract::todo_list::{
+    LegacyDataKey, LegacyTodo, Priority, TodoMeta, TodoStatus, Todolist, TodolistClient,
+};
+
+use crate::event::{from_env, from_rpc_json, to_rpc_events, TodoEvent};
+use crate::mirror::Mirror;
+
+fn setup_with_admin() -> (Env, TodolistClient<'static>, Address, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(Todolist, (admin.clone(),));
+    let client = TodolistClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+
+    (env, client, owner, admin)
+}
+
+fn setup() -> (Env, TodolistClient<'static>, Address) {
+    let (env, client, owner, _) = setup_with_admin();
+    (env, client, owner)
+}
+
+fn meta(env: &Env, priority: Priority, due: Option<u64>) -> TodoMeta {
+    TodoMeta {
+        priority,
+        due_ledger_timestamp: due,
+        tags: vec![env, symbol_short!("garage")],
+    }
+}
+
+// `events().all()` only holds the last invocation, so collect after every
+// call the way an indexer polling a node would
+fn index(env: &Env, client: &TodolistClient, mirror: &Mirror, log: &mut std::vec::Vec<TodoEvent>) {
+    let events = from_env(env, &client.address);
+    mirror.apply_all(&events).unwrap();
+    log.extend(events);
+}
+
+#[test]
+fn test_mirror_follows_contract() {
+    let (env, client, owner) = setup();
+    let mirror = Mirror::in_memory().unwrap();
+    let mut log = std::vec::Vec::new();
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+
+    for _ in 0..3 {
+        client.create_todo(
+            &owner,
+            &owner,
+            &title,
+            &description,
+            &meta(&env, Priority::High, Some(100)),
+        );
+        index(&env, &client, &mirror, &mut log);
+    }
+
+    client.update_todo(
+        &owner,
+        &owner,
+        &1,
+        &String::from_str(&env, "Sleep"),
+        &description,
+    );
+    index(&env, &client, &mirror, &mut log);
+
+    client.set_status(&owner, &owner, &2, &TodoStatus::InProgress);
+    index(&env, &client, &mirror, &mut log);
+
+    client.complete_todo(&owner, &owner, &2);
+    index(&env, &client, &mirror, &mut log);
+
+    client.delete_todo(&owner, &owner, &3);
+    index(&env, &client, &mirror, &mut log);
+
+    assert_eq!(log.len(), 7);
+
+    let owner_key = owner.to_string().to_string();
+    let rows = mirror.todos(&owner_key).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].title, "Sleep");
+    assert_eq!(rows[0].tags, ["garage"]);
+    assert_eq!(rows[1].status, "Done");
+    assert_eq!(mirror.todo(&owner_key, 3).unwrap(), None);
+
+    // The mirror answers the same as the contract
+    let on_chain = client.get_todos_enum(&owner);
+    for (row, todo) in rows.iter().zip(on_chain.iter()) {
+        assert_eq!(row.id, todo.id);
+        assert_eq!(row.title, todo.title.to_string());
+        assert_eq!(row.status, format!("{:?}", todo.status));
+    }
+}
+
+#[test]
+fn test_mirror_sees_migrated_todos() {
+    let (env, client, owner, admin) = setup_with_admin();
+    let mirror = Mirror::in_memory().unwrap();
+    let mut log = std::vec::Vec::new();
+
+    client.create_todo(
+        &owner,
+        &owner,
+        &String::from_str(&env, "Wake up"),
+        &String::from_str(&env, "Class at 9"),
+        &meta(&env, Priority::High, None),
+    );
+    index(&env, &client, &mirror, &mut log);
+
+    // The first release's shared list, only ever written to storage
+    let legacy = vec![
+        &env,
+        LegacyTodo {
+            id: 1,
+            title: String::from_str(&env, "Go home!!!"),
+            description: String::from_str(&env, "From Garage to the hostel"),
+            status: true,
+        },
+    ];
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .set(&LegacyDataKey::Todos, &legacy);
+    });
+
+    client.migrate_legacy(&admin, &owner);
+    index(&env, &client, &mirror, &mut log);
+
+    let rows = mirror.todos(&owner.to_string().to_string()).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1].id, 2);
+    assert_eq!(rows[1].title, "Go home!!!");
+    assert_eq!(rows[1].status, "Done");
+    assert_eq!(rows.len() as u32, client.get_todos_enum(&owner).len());
+}
+
+#[test]
+fn test_mirror_queries() {
+    let (env, client, owner) = setup();
+    let mirror = Mirror::in_memory().unwrap();
+    let mut log = std::vec::Vec::new();
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(&env, Priority::High, Some(100)),
+    );
+    index(&env, &client, &mirror, &mut log);
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(&env, Priority::Low, Some(900)),
+    );
+    index(&env, &client, &mirror, &mut log);
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(&env, Priority::Low, Some(50)),
+    );
+    index(&env, &client, &mirror, &mut log);
+    client.complete_todo(&owner, &owner, &3);
+    index(&env, &client, &mirror, &mut log);
+
+    env.ledger().set_timestamp(500);
+    let owner_key = owner.to_string().to_string();
+
+    let overdue = mirror
+        .overdue(&owner_key, env.ledger().timestamp())
+        .unwrap();
+    let on_chain = client.get_overdue(&owner);
+    assert_eq!(overdue.len(), 1);
+    assert_eq!(overdue[0].id, on_chain.get(0).unwrap().id);
+
+    assert_eq!(mirror.with_status(&owner_key, "Done").unwrap().len(), 1);
+    assert_eq!(mirror.with_status(&owner_key, "Open").unwrap().len(), 2);
+    assert!(mirror
+        .todos(&Address::generate(&env).to_string().to_string())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_rpc_json_dump() {
+    let (env, client, owner) = setup();
+    let title = String::from_str(&env, "Go home!!!");
+    let description = String::from_str(&env, "From Garage to the hostel");
+    let mut dump = std::vec::Vec::new();
+
+    client.create_todo(
+        &owner,
+        &owner,
+        &title,
+        &description,
+        &meta(&env, Priority::Medium, None),
+    );
+    dump.extend(to_rpc_events(&env));
+    client.complete_todo(&owner, &owner, &1);
+    dump.extend(to_rpc_events(&env));
+
+    let json = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "result": { "events": dump, "latestLedger": 100 }
+    })
+    .to_string();
+
+    let contract_id = client.address.to_string().to_string();
+    let events = from_rpc_json(&env, &json, Some(&contract_id)).unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(from_rpc_json(&env, &json, Some("CSOMEOTHERCONTRACT"))
+        .unwrap()
+        .is_empty());
+
+    let mirror = Mirror::in_memory().unwrap();
+    mirror.apply_all(&events).unwrap();
+
+    let row = mirror
+        .todo(&owner.to_string().to_string(), 1)
+        .unwrap()
+        .unwrap();
+    assert_eq!(row.status, "Done");
+    assert_eq!(row.priority, "Medium");
+    assert_eq!(row.due_ledger_timestamp, None);
+}
+
+#[test]
+fn test_unknown_events_are_skipped() {
+    let env = Env::default();
+    let topics: Vec<soroban_sdk::Val> = Vec::new(&env);
+
+    assert_eq!(crate::event::decode(&env, &topics, ().into()), None);
+}
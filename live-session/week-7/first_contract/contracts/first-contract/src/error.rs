@@ -0,0 +1,27 @@
+use soroban_sdk::contracterror;
+use upgradeable::UpgradeError;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum MathError {
+    Overflow = 1,
+    DivisionByZero = 2,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum AdminError {
+    Unauthorized = 5,
+    AlreadyMigrated = 6,
+}
+
+impl From<UpgradeError> for AdminError {
+    fn from(error: UpgradeError) -> Self {
+        match error {
+            UpgradeError::Unauthorized => AdminError::Unauthorized,
+            UpgradeError::AlreadyMigrated => AdminError::AlreadyMigrated,
+        }
+    }
+}
@@ -1,4 +1,9 @@
-use soroban_sdk::{contract, contractimpl};
+use soroban_sdk::{contract, contractimpl, Address, BytesN, Env};
+
+use crate::error::{AdminError, MathError};
+use crate::math::{self, CheckedMath, Rounding};
+
+pub const VERSION: u32 = 1;
 
 #[contract]
 pub struct FirstContract;
@@ -12,4 +17,112 @@ impl FirstContract {
     pub fn sub(a: i32, b: i32) -> i32 {
         a - b
     }
+
+    pub fn checked_add(a: i32, b: i32) -> Result<i32, MathError> {
+        a.try_add(b)
+    }
+
+    pub fn checked_sub(a: i32, b: i32) -> Result<i32, MathError> {
+        a.try_sub(b)
+    }
+
+    pub fn checked_mul(a: i32, b: i32) -> Result<i32, MathError> {
+        a.try_mul(b)
+    }
+
+    pub fn checked_div(a: i32, b: i32) -> Result<i32, MathError> {
+        a.try_div(b)
+    }
+
+    pub fn checked_pow(base: i32, exp: u32) -> Result<i32, MathError> {
+        base.try_pow(exp)
+    }
+}
+
+// Token amounts are i128 (SEP-41) or u128, so the same operations are
+// exposed for both.
+#[contractimpl]
+impl FirstContract {
+    pub fn checked_add_i128(a: i128, b: i128) -> Result<i128, MathError> {
+        a.try_add(b)
+    }
+
+    pub fn checked_sub_i128(a: i128, b: i128) -> Result<i128, MathError> {
+        a.try_sub(b)
+    }
+
+    pub fn checked_mul_i128(a: i128, b: i128) -> Result<i128, MathError> {
+        a.try_mul(b)
+    }
+
+    pub fn checked_div_i128(a: i128, b: i128) -> Result<i128, MathError> {
+        a.try_div(b)
+    }
+
+    pub fn checked_pow_i128(base: i128, exp: u32) -> Result<i128, MathError> {
+        base.try_pow(exp)
+    }
+
+    pub fn mul_div_i128(
+        env: Env,
+        a: i128,
+        b: i128,
+        denominator: i128,
+        rounding: Rounding,
+    ) -> Result<i128, MathError> {
+        math::mul_div_i128(&env, a, b, denominator, rounding)
+    }
+
+    pub fn checked_add_u128(a: u128, b: u128) -> Result<u128, MathError> {
+        a.try_add(b)
+    }
+
+    pub fn checked_sub_u128(a: u128, b: u128) -> Result<u128, MathError> {
+        a.try_sub(b)
+    }
+
+    pub fn checked_mul_u128(a: u128, b: u128) -> Result<u128, MathError> {
+        a.try_mul(b)
+    }
+
+    pub fn checked_div_u128(a: u128, b: u128) -> Result<u128, MathError> {
+        a.try_div(b)
+    }
+
+    pub fn checked_pow_u128(base: u128, exp: u32) -> Result<u128, MathError> {
+        base.try_pow(exp)
+    }
+
+    pub fn mul_div_u128(
+        env: Env,
+        a: u128,
+        b: u128,
+        denominator: u128,
+        rounding: Rounding,
+    ) -> Result<u128, MathError> {
+        math::mul_div_u128(&env, a, b, denominator, rounding)
+    }
+}
+
+#[contractimpl]
+impl FirstContract {
+    pub fn __constructor(env: Env, admin: Address) {
+        upgradeable::init(&env, &admin, VERSION);
+    }
+
+    pub fn version(env: Env) -> u32 {
+        upgradeable::version(&env)
+    }
+
+    pub fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) -> Result<(), AdminError> {
+        upgradeable::upgrade(&env, &caller, new_wasm_hash).map_err(AdminError::from)
+    }
+
+    pub fn migrate(env: Env, caller: Address) -> Result<u32, AdminError> {
+        upgradeable::migrate(&env, &caller, VERSION, Self::migrate_from).map_err(AdminError::from)
+    }
+}
+
+impl FirstContract {
+    fn migrate_from(_env: &Env, _version: u32) {}
 }
@@ -1,4 +1,6 @@
 #![no_std]
 
-mod first_contract;
+pub mod error;
+pub mod first_contract;
+pub mod math;
 mod test;
@@ -0,0 +1,154 @@
+use soroban_sdk::{contracttype, Env, U256};
+
+use crate::error::MathError;
+
+/// What `mul_div` does with a remainder.
+#[contracttype]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Towards negative infinity
+    Floor,
+    /// Towards positive infinity
+    Ceil,
+    /// To the closest result, halves away from zero
+    Nearest,
+}
+
+/// Arithmetic that reports overflow instead of relying on the release
+/// profile's `overflow-checks` to trap.
+pub trait CheckedMath: Sized {
+    fn try_add(self, rhs: Self) -> Result<Self, MathError>;
+    fn try_sub(self, rhs: Self) -> Result<Self, MathError>;
+    fn try_mul(self, rhs: Self) -> Result<Self, MathError>;
+    fn try_div(self, rhs: Self) -> Result<Self, MathError>;
+    fn try_pow(self, exp: u32) -> Result<Self, MathError>;
+}
+
+macro_rules! impl_checked_math {
+    ($($t:ty),*) => {$(
+        impl CheckedMath for $t {
+            fn try_add(self, rhs: Self) -> Result<Self, MathError> {
+                self.checked_add(rhs).ok_or(MathError::Overflow)
+            }
+
+            fn try_sub(self, rhs: Self) -> Result<Self, MathError> {
+                self.checked_sub(rhs).ok_or(MathError::Overflow)
+            }
+
+            fn try_mul(self, rhs: Self) -> Result<Self, MathError> {
+                self.checked_mul(rhs).ok_or(MathError::Overflow)
+            }
+
+            // `MIN / -1` is the only other way division can fail
+            fn try_div(self, rhs: Self) -> Result<Self, MathError> {
+                if rhs == 0 {
+                    return Err(MathError::DivisionByZero);
+                }
+                self.checked_div(rhs).ok_or(MathError::Overflow)
+            }
+
+            fn try_pow(self, exp: u32) -> Result<Self, MathError> {
+                self.checked_pow(exp).ok_or(MathError::Overflow)
+            }
+        }
+    )*};
+}
+
+impl_checked_math!(i32, i128, u128);
+
+// Which way to move a truncated quotient, once the sign is known
+#[derive(Copy, Clone)]
+enum Direction {
+    Down,
+    Up,
+    HalfUp,
+}
+
+// `a * b / denominator` on magnitudes, with the product held in 256 bits
+fn mul_div_abs(
+    env: &Env,
+    a: u128,
+    b: u128,
+    denominator: u128,
+    direction: Direction,
+) -> Result<U256, MathError> {
+    if denominator == 0 {
+        return Err(MathError::DivisionByZero);
+    }
+
+    let denominator = U256::from_u128(env, denominator);
+    let product = U256::from_u128(env, a).mul(&U256::from_u128(env, b));
+    let quotient = product.div(&denominator);
+    let remainder = product.rem_euclid(&denominator);
+
+    let round_up = match direction {
+        Direction::Down => false,
+        Direction::Up => remainder != U256::from_u32(env, 0),
+        Direction::HalfUp => remainder.mul(&U256::from_u32(env, 2)) >= denominator,
+    };
+
+    if round_up {
+        Ok(quotient.add(&U256::from_u32(env, 1)))
+    } else {
+        Ok(quotient)
+    }
+}
+
+/// `a * b / denominator` without overflowing on the intermediate product,
+/// e.g. applying a rate of `b / denominator` to an amount.
+pub fn mul_div_u128(
+    env: &Env,
+    a: u128,
+    b: u128,
+    denominator: u128,
+    rounding: Rounding,
+) -> Result<u128, MathError> {
+    let direction = match rounding {
+        Rounding::Floor => Direction::Down,
+        Rounding::Ceil => Direction::Up,
+        Rounding::Nearest => Direction::HalfUp,
+    };
+
+    mul_div_abs(env, a, b, denominator, direction)?
+        .to_u128()
+        .ok_or(MathError::Overflow)
+}
+
+/// Signed `a * b / denominator`. `Floor` and `Ceil` round towards negative
+/// and positive infinity, so a negative result rounds the other way from a
+/// positive one.
+pub fn mul_div_i128(
+    env: &Env,
+    a: i128,
+    b: i128,
+    denominator: i128,
+    rounding: Rounding,
+) -> Result<i128, MathError> {
+    let negative = (a < 0) ^ (b < 0) ^ (denominator < 0);
+    let direction = match (rounding, negative) {
+        (Rounding::Floor, false) | (Rounding::Ceil, true) => Direction::Down,
+        (Rounding::Floor, true) | (Rounding::Ceil, false) => Direction::Up,
+        (Rounding::Nearest, _) => Direction::HalfUp,
+    };
+
+    let magnitude = mul_div_abs(
+        env,
+        a.unsigned_abs(),
+        b.unsigned_abs(),
+        denominator.unsigned_abs(),
+        direction,
+    )?
+    .to_u128()
+    .ok_or(MathError::Overflow)?;
+
+    if negative {
+        // `i128::MIN` has no positive counterpart, so negate in unsigned
+        // space and let the one extra value through
+        if magnitude > i128::MIN.unsigned_abs() {
+            return Err(MathError::Overflow);
+        }
+        Ok((magnitude as i128).wrapping_neg())
+    } else {
+        i128::try_from(magnitude).map_err(|_| MathError::Overflow)
+    }
+}
@@ -1,13 +1,418 @@
 #![cfg(test)]
 
+use crate::error::{AdminError, MathError};
 use crate::first_contract::{FirstContract, FirstContractClient};
+use crate::math::{mul_div_i128, mul_div_u128, CheckedMath, Rounding};
 
-use super::*;
-use soroban_sdk::{vec, Env, String};
+use soroban_sdk::{testutils::Address as _, Address, BytesN, Env};
+use upgradeable::testutils::{empty_wasm, install_next_release};
+
+const ROUNDINGS: [Rounding; 3] = [Rounding::Floor, Rounding::Ceil, Rounding::Nearest];
+
+fn setup_with_admin<'a>() -> (Env, FirstContractClient<'a>, Address) {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let contract_id = env.register(FirstContract, (admin.clone(),));
+    let client = FirstContractClient::new(&env, &contract_id);
+
+    (env, client, admin)
+}
+
+fn setup<'a>() -> (Env, FirstContractClient<'a>) {
+    let (env, client, _) = setup_with_admin();
+    (env, client)
+}
 
 #[test]
 fn test() {
+    let (_, client) = setup();
+
+    assert_eq!(client.add(&2, &3), 5);
+    assert_eq!(client.sub(&2, &3), -1);
+}
+
+#[test]
+fn test_checked_i32() {
+    let (_, client) = setup();
+
+    assert_eq!(client.checked_add(&2, &3), 5);
+    assert_eq!(client.checked_sub(&2, &3), -1);
+    assert_eq!(client.checked_mul(&-4, &3), -12);
+    assert_eq!(client.checked_div(&-7, &2), -3);
+    assert_eq!(client.checked_pow(&-2, &3), -8);
+
+    assert_eq!(
+        client.try_checked_add(&i32::MAX, &1),
+        Err(Ok(MathError::Overflow))
+    );
+    assert_eq!(
+        client.try_checked_sub(&i32::MIN, &1),
+        Err(Ok(MathError::Overflow))
+    );
+    assert_eq!(
+        client.try_checked_mul(&i32::MIN, &-1),
+        Err(Ok(MathError::Overflow))
+    );
+    assert_eq!(
+        client.try_checked_div(&1, &0),
+        Err(Ok(MathError::DivisionByZero))
+    );
+    assert_eq!(
+        client.try_checked_div(&i32::MIN, &-1),
+        Err(Ok(MathError::Overflow))
+    );
+    assert_eq!(
+        client.try_checked_pow(&2, &31),
+        Err(Ok(MathError::Overflow))
+    );
+    assert_eq!(client.checked_pow(&-2, &31), i32::MIN);
+    assert_eq!(client.checked_pow(&0, &0), 1);
+}
+
+#[test]
+fn test_checked_i128() {
+    let (_, client) = setup();
+
+    assert_eq!(client.checked_add_i128(&i128::MAX, &i128::MIN), -1);
+    assert_eq!(client.checked_sub_i128(&-1, &i128::MAX), i128::MIN);
+    assert_eq!(client.checked_mul_i128(&(1 << 62), &(1 << 64)), 1 << 126);
+    assert_eq!(client.checked_div_i128(&i128::MIN, &1), i128::MIN);
+    assert_eq!(client.checked_pow_i128(&10, &38), 10_i128.pow(38));
+
+    assert_eq!(
+        client.try_checked_add_i128(&i128::MAX, &1),
+        Err(Ok(MathError::Overflow))
+    );
+    assert_eq!(
+        client.try_checked_sub_i128(&i128::MIN, &1),
+        Err(Ok(MathError::Overflow))
+    );
+    assert_eq!(
+        client.try_checked_mul_i128(&(1 << 64), &(1 << 63)),
+        Err(Ok(MathError::Overflow))
+    );
+    assert_eq!(
+        client.try_checked_div_i128(&0, &0),
+        Err(Ok(MathError::DivisionByZero))
+    );
+    assert_eq!(
+        client.try_checked_div_i128(&i128::MIN, &-1),
+        Err(Ok(MathError::Overflow))
+    );
+    assert_eq!(
+        client.try_checked_pow_i128(&10, &39),
+        Err(Ok(MathError::Overflow))
+    );
+}
+
+#[test]
+fn test_checked_u128() {
+    let (_, client) = setup();
+
+    assert_eq!(client.checked_add_u128(&(u128::MAX - 1), &1), u128::MAX);
+    assert_eq!(client.checked_sub_u128(&1, &1), 0);
+    assert_eq!(client.checked_mul_u128(&(1 << 64), &(1 << 63)), 1 << 127);
+    assert_eq!(client.checked_div_u128(&u128::MAX, &u128::MAX), 1);
+    assert_eq!(client.checked_pow_u128(&2, &127), 1 << 127);
+
+    assert_eq!(
+        client.try_checked_add_u128(&u128::MAX, &1),
+        Err(Ok(MathError::Overflow))
+    );
+    assert_eq!(
+        client.try_checked_sub_u128(&0, &1),
+        Err(Ok(MathError::Overflow))
+    );
+    assert_eq!(
+        client.try_checked_mul_u128(&(1 << 64), &(1 << 64)),
+        Err(Ok(MathError::Overflow))
+    );
+    assert_eq!(
+        client.try_checked_div_u128(&1, &0),
+        Err(Ok(MathError::DivisionByZero))
+    );
+    assert_eq!(
+        client.try_checked_pow_u128(&2, &128),
+        Err(Ok(MathError::Overflow))
+    );
+}
+
+#[test]
+fn test_checked_math_trait() {
+    assert_eq!(7_i32.try_add(-7), Ok(0));
+    assert_eq!(i32::MAX.try_mul(2), Err(MathError::Overflow));
+    assert_eq!(i128::MIN.try_pow(1), Ok(i128::MIN));
+    assert_eq!(i128::MIN.try_pow(2), Err(MathError::Overflow));
+    assert_eq!(5_u128.try_div(0), Err(MathError::DivisionByZero));
+    assert_eq!(u128::MAX.try_pow(0), Ok(1));
+}
+
+#[test]
+fn test_mul_div_u128_rounding() {
     let env = Env::default();
-    let contract_id = env.register(FirstContract, ());
-    let client = FirstContractClient::new(&env, &contract_id);
+
+    // 10 * 1 / 4 = 2.5, 10 * 1 / 3 = 3.33, 11 * 1 / 3 = 3.67
+    let cases = [
+        (10, 1, 4, [2, 3, 3]),
+        (10, 1, 3, [3, 4, 3]),
+        (11, 1, 3, [3, 4, 4]),
+        (12, 1, 3, [4, 4, 4]),
+        (0, 5, 3, [0, 0, 0]),
+    ];
+    for (a, b, denominator, expected) in cases {
+        for (rounding, expected) in ROUNDINGS.iter().zip(expected) {
+            assert_eq!(
+                mul_div_u128(&env, a, b, denominator, *rounding),
+                Ok(expected),
+                "{} * {} / {} {:?}",
+                a,
+                b,
+                denominator,
+                rounding
+            );
+        }
+    }
+}
+
+#[test]
+fn test_mul_div_i128_rounding() {
+    let env = Env::default();
+
+    // Floor and Ceil follow the number line, Nearest is symmetric
+    let cases = [
+        (10, 1, 4, [2, 3, 3]),
+        (-10, 1, 4, [-3, -2, -3]),
+        (10, -1, 4, [-3, -2, -3]),
+        (10, 1, -4, [-3, -2, -3]),
+        (-10, -1, -4, [-3, -2, -3]),
+        (-10, -1, 4, [2, 3, 3]),
+        (-10, 1, 3, [-4, -3, -3]),
+        (-11, 1, 3, [-4, -3, -4]),
+        (-12, 1, 3, [-4, -4, -4]),
+        (0, -5, 3, [0, 0, 0]),
+    ];
+    for (a, b, denominator, expected) in cases {
+        for (rounding, expected) in ROUNDINGS.iter().zip(expected) {
+            assert_eq!(
+                mul_div_i128(&env, a, b, denominator, *rounding),
+                Ok(expected),
+                "{} * {} / {} {:?}",
+                a,
+                b,
+                denominator,
+                rounding
+            );
+        }
+    }
+}
+
+#[test]
+fn test_mul_div_wide_intermediate() {
+    let env = Env::default();
+
+    // The products below do not fit in 128 bits
+    for rounding in ROUNDINGS {
+        assert_eq!(
+            mul_div_u128(&env, u128::MAX, u128::MAX, u128::MAX, rounding),
+            Ok(u128::MAX)
+        );
+        assert_eq!(
+            mul_div_i128(&env, i128::MAX, i128::MAX, i128::MAX, rounding),
+            Ok(i128::MAX)
+        );
+        assert_eq!(
+            mul_div_i128(&env, i128::MIN, i128::MIN, i128::MIN, rounding),
+            Ok(i128::MIN)
+        );
+        assert_eq!(
+            mul_div_i128(&env, i128::MIN, i128::MAX, i128::MAX, rounding),
+            Ok(i128::MIN)
+        );
+    }
+
+    // 1.5% of the whole supply, in 7-decimal fixed point
+    let supply = 10_i128.pow(30);
+    assert_eq!(
+        mul_div_i128(&env, supply, 150_000, 10_000_000, Rounding::Floor),
+        Ok(supply / 1000 * 15)
+    );
+}
+
+#[test]
+fn test_mul_div_errors() {
+    let env = Env::default();
+
+    for rounding in ROUNDINGS {
+        assert_eq!(
+            mul_div_u128(&env, 1, 1, 0, rounding),
+            Err(MathError::DivisionByZero)
+        );
+        assert_eq!(
+            mul_div_i128(&env, 1, 1, 0, rounding),
+            Err(MathError::DivisionByZero)
+        );
+        assert_eq!(
+            mul_div_u128(&env, u128::MAX, 2, 1, rounding),
+            Err(MathError::Overflow)
+        );
+        assert_eq!(
+            mul_div_i128(&env, i128::MAX, 2, 1, rounding),
+            Err(MathError::Overflow)
+        );
+        assert_eq!(
+            mul_div_i128(&env, i128::MIN, -1, 1, rounding),
+            Err(MathError::Overflow)
+        );
+    }
+
+    // Rounding away from the truncated quotient can be the step that
+    // overflows: 7 * b = 2^129 - 1 and 3 * c = 2^128 - 1 are both odd, and
+    // f * g = 2^128 + 1
+    let b = 2 * (u128::MAX / 7) + 1;
+    assert_eq!(mul_div_u128(&env, 7, b, 2, Rounding::Floor), Ok(u128::MAX));
+    assert_eq!(
+        mul_div_u128(&env, 7, b, 2, Rounding::Ceil),
+        Err(MathError::Overflow)
+    );
+
+    let c = (u128::MAX / 3) as i128;
+    assert_eq!(mul_div_i128(&env, 3, c, 2, Rounding::Floor), Ok(i128::MAX));
+    assert_eq!(
+        mul_div_i128(&env, 3, c, 2, Rounding::Nearest),
+        Err(MathError::Overflow)
+    );
+
+    let (f, g) = (59_649_589_127_497_217, 5_704_689_200_685_129_054_721);
+    assert_eq!(mul_div_i128(&env, -f, g, 2, Rounding::Ceil), Ok(i128::MIN));
+    assert_eq!(
+        mul_div_i128(&env, -f, g, 2, Rounding::Floor),
+        Err(MathError::Overflow)
+    );
+}
+
+#[test]
+fn test_mul_div_through_client() {
+    let (_, client) = setup();
+
+    assert_eq!(client.mul_div_i128(&-10, &1, &4, &Rounding::Nearest), -3);
+    assert_eq!(client.mul_div_u128(&10, &1, &4, &Rounding::Floor), 2);
+    assert_eq!(
+        client.try_mul_div_i128(&1, &1, &0, &Rounding::Floor),
+        Err(Ok(MathError::DivisionByZero))
+    );
+    assert_eq!(
+        client.try_mul_div_u128(&u128::MAX, &2, &1, &Rounding::Floor),
+        Err(Ok(MathError::Overflow))
+    );
+}
+
+mod v2 {
+    use crate::error::AdminError;
+    use crate::first_contract::FirstContract;
+    use soroban_sdk::{contract, contractimpl, symbol_short, vec, Address, Env, Vec};
+
+    #[contract]
+    pub struct FirstContractV2;
+
+    #[contractimpl]
+    impl FirstContractV2 {
+        pub fn add(a: i32, b: i32) -> i32 {
+            FirstContract::add(a, b)
+        }
+
+        pub fn version(env: Env) -> u32 {
+            FirstContract::version(env)
+        }
+
+        pub fn migrate(env: Env, caller: Address) -> Result<u32, AdminError> {
+            upgradeable::migrate(&env, &caller, 2, |env, from| {
+                let mut steps = Self::steps(env.clone());
+                steps.push_back(from);
+                env.storage()
+                    .instance()
+                    .set(&symbol_short!("steps"), &steps);
+            })
+            .map_err(AdminError::from)
+        }
+
+        // Versions the migration hook ran for
+        pub fn steps(env: Env) -> Vec<u32> {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("steps"))
+                .unwrap_or(vec![&env])
+        }
+    }
+}
+
+#[test]
+fn test_constructor_sets_version() {
+    let (env, client, admin) = setup_with_admin();
+    assert_eq!(client.version(), 1);
+
+    env.mock_all_auths();
+    assert_eq!(
+        client.try_migrate(&admin),
+        Err(Ok(AdminError::AlreadyMigrated))
+    );
+}
+
+#[test]
+fn test_upgrade_requires_admin() {
+    let (env, client, admin) = setup_with_admin();
+    let stranger = Address::generate(&env);
+    let hash = BytesN::from_array(&env, &[0; 32]);
+
+    // Without the admin's signature, naming the admin is not enough
+    assert!(client.try_upgrade(&admin, &hash).is_err());
+
+    env.mock_all_auths();
+    assert_eq!(
+        client.try_upgrade(&stranger, &hash),
+        Err(Ok(AdminError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_migrate(&stranger),
+        Err(Ok(AdminError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_upgrade_replaces_code() {
+    let (env, client, admin) = setup_with_admin();
+    env.mock_all_auths();
+
+    let hash = env.deployer().upload_contract_wasm(empty_wasm(&env));
+    client.upgrade(&admin, &hash);
+
+    // The new code has no functions at all
+    assert!(client.try_add(&2, &3).is_err());
+}
+
+#[test]
+fn test_upgrade_v1_to_v2() {
+    let (env, client, admin) = setup_with_admin();
+    env.mock_all_auths();
+    let stranger = Address::generate(&env);
+
+    install_next_release(&env, &client.address, v2::FirstContractV2);
+    let client = v2::FirstContractV2Client::new(&env, &client.address);
+
+    // Storage is untouched until the admin migrates
+    assert_eq!(client.version(), 1);
+    assert_eq!(
+        client.try_migrate(&stranger),
+        Err(Ok(AdminError::Unauthorized))
+    );
+
+    assert_eq!(client.migrate(&admin), 2);
+    assert_eq!(client.version(), 2);
+    assert_eq!(client.steps(), soroban_sdk::vec![&env, 1]);
+
+    assert_eq!(
+        client.try_migrate(&admin),
+        Err(Ok(AdminError::AlreadyMigrated))
+    );
+    assert_eq!(client.steps().len(), 1);
+    assert_eq!(client.add(&2, &3), 5);
 }
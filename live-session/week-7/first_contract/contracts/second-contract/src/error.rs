@@ -1,8 +1,22 @@
 use soroban_sdk::contracterror;
+use upgradeable::UpgradeError;
 
 #[contracterror]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u32)]
 pub enum ContractErrors {
     LessThan = 1,
+    Unauthorized = 4,
+    InvalidCallee = 5,
+    CalleeFailed = 6,
+    AlreadyMigrated = 7,
+}
+
+impl From<UpgradeError> for ContractErrors {
+    fn from(error: UpgradeError) -> Self {
+        match error {
+            UpgradeError::Unauthorized => ContractErrors::Unauthorized,
+            UpgradeError::AlreadyMigrated => ContractErrors::AlreadyMigrated,
+        }
+    }
 }
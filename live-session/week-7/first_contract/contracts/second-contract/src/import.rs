@@ -1,4 +1,15 @@
 pub mod contract_a {
-    use soroban_sdk::contractimport;
-    contractimport!(file = "../../target/wasm32v1-none/release/first_contract.wasm");
+    use soroban_sdk::{contractclient, Env};
+
+    // The subset of `FirstContract` this contract calls. Declaring it here
+    // instead of importing the built wasm means this crate compiles and tests
+    // without building `first-contract` for wasm first; the tests register
+    // the real `FirstContract` to keep the two in step.
+    #[contractclient(name = "Client")]
+    pub trait FirstContract {
+        fn add(env: Env, a: i32, b: i32) -> i32;
+        fn sub(env: Env, a: i32, b: i32) -> i32;
+        fn checked_add(env: Env, a: i32, b: i32) -> Result<i32, soroban_sdk::Error>;
+        fn checked_sub(env: Env, a: i32, b: i32) -> Result<i32, soroban_sdk::Error>;
+    }
 }
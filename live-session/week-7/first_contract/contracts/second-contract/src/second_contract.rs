@@ -1,26 +1,104 @@
 use crate::error::ContractErrors;
 use crate::import::contract_a::Client;
-use soroban_sdk::{contract, contractimpl};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, Symbol, Val, Vec};
+
+pub const VERSION: u32 = 1;
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Allowed(Address),
+}
 
 #[contract]
 pub struct SecondContract;
 
 #[contractimpl]
 impl SecondContract {
+    pub fn __constructor(env: Env, admin: Address) {
+        upgradeable::init(&env, &admin, VERSION);
+    }
+
+    pub fn version(env: Env) -> u32 {
+        upgradeable::version(&env)
+    }
+
+    /// The allowlist is kept across upgrades.
+    pub fn upgrade(
+        env: Env,
+        caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), ContractErrors> {
+        upgradeable::upgrade(&env, &caller, new_wasm_hash).map_err(ContractErrors::from)
+    }
+
+    pub fn migrate(env: Env, caller: Address) -> Result<u32, ContractErrors> {
+        upgradeable::migrate(&env, &caller, VERSION, Self::migrate_from)
+            .map_err(ContractErrors::from)
+    }
+
+    /// Lets the router call `contract`.
+    pub fn allow(env: Env, caller: Address, contract: Address) -> Result<(), ContractErrors> {
+        Self::require_admin(&env, &caller)?;
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Allowed(contract), &true);
+        Ok(())
+    }
+
+    pub fn disallow(env: Env, caller: Address, contract: Address) -> Result<(), ContractErrors> {
+        Self::require_admin(&env, &caller)?;
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Allowed(contract));
+        Ok(())
+    }
+
+    pub fn is_allowed(env: Env, contract: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Allowed(contract))
+    }
+
+    /// Calls any function on an allowed contract. A callee that panics or
+    /// returns an error comes back as `CalleeFailed` instead of aborting
+    /// this call.
+    ///
+    /// Admin only: the callee sees this contract as the invoker, so
+    /// anything it authorizes for the router's own address (such as
+    /// spending its tokens) would otherwise be open to any caller.
+    pub fn route(
+        env: Env,
+        caller: Address,
+        contract: Address,
+        func: Symbol,
+        args: Vec<Val>,
+    ) -> Result<Val, ContractErrors> {
+        Self::require_admin(&env, &caller)?;
+        Self::require_allowed(&env, &contract)?;
+
+        match env.try_invoke_contract::<Val, soroban_sdk::Error>(&contract, &func, args) {
+            Ok(Ok(result)) => Ok(result),
+            _ => Err(ContractErrors::CalleeFailed),
+        }
+    }
+
     pub fn add_from_first_contract(
         env: soroban_sdk::Env,
         contract_address: soroban_sdk::Address,
         a: i32,
         b: i32,
-    ) -> Result<(), ContractErrors> {
+    ) -> Result<i32, ContractErrors> {
         if a < 50 {
             return Err(ContractErrors::LessThan);
         }
 
-        let new = Client::new(&env, &contract_address);
+        let new = Self::callee(&env, &contract_address)?;
 
-        new.add(&a, &b);
-        Ok(())
+        match new.try_checked_add(&a, &b) {
+            Ok(Ok(sum)) => Ok(sum),
+            _ => Err(ContractErrors::CalleeFailed),
+        }
     }
 
     pub fn sub_from_first_contract(
@@ -33,8 +111,31 @@ impl SecondContract {
             return Err(ContractErrors::LessThan);
         }
 
-        let new = Client::new(&env, &contract_address);
+        let new = Self::callee(&env, &contract_address)?;
+
+        match new.try_checked_sub(&a, &b) {
+            Ok(Ok(difference)) => Ok(difference),
+            _ => Err(ContractErrors::CalleeFailed),
+        }
+    }
+}
+
+impl SecondContract {
+    pub(crate) fn migrate_from(_env: &Env, _version: u32) {}
+
+    fn require_admin(env: &Env, caller: &Address) -> Result<(), ContractErrors> {
+        upgradeable::require_admin(env, caller).map_err(ContractErrors::from)
+    }
+
+    fn require_allowed(env: &Env, contract: &Address) -> Result<(), ContractErrors> {
+        if !Self::is_allowed(env.clone(), contract.clone()) {
+            return Err(ContractErrors::InvalidCallee);
+        }
+        Ok(())
+    }
 
-        Ok(new.sub(&a, &b))
+    fn callee<'a>(env: &Env, contract: &Address) -> Result<Client<'a>, ContractErrors> {
+        Self::require_allowed(env, contract)?;
+        Ok(Client::new(env, contract))
     }
 }
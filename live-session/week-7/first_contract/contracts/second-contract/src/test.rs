@@ -1,13 +1,277 @@
 #![cfg(test)]
 
+use crate::error::ContractErrors;
 use crate::second_contract::{SecondContract, SecondContractClient};
+use first_contract::first_contract::FirstContract;
+use soroban_sdk::{
+    symbol_short,
+    testutils::Address as _,
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, BytesN, Env, IntoVal, Symbol, TryFromVal, Val,
+};
+use upgradeable::testutils::install_next_release;
 
-use super::*;
-use soroban_sdk::{vec, Env, String};
+fn setup<'a>() -> (Env, SecondContractClient<'a>, Address, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    // Both contracts run natively in the same `Env`, no wasm needed
+    let first_id = env.register(FirstContract, (admin.clone(),));
+    let contract_id = env.register(SecondContract, (admin.clone(),));
+    let client = SecondContractClient::new(&env, &contract_id);
+
+    client.allow(&admin, &first_id);
+
+    (env, client, admin, first_id)
+}
 
 #[test]
 fn test() {
-    let env = Env::default();
-    let contract_id = env.register(SecondContract, ());
-    let client = SecondContractClient::new(&env, &contract_id);
+    let (_, client, _, first_id) = setup();
+
+    assert_eq!(client.add_from_first_contract(&first_id, &60, &2), 62);
+    assert_eq!(client.sub_from_first_contract(&first_id, &60, &2), 58);
+}
+
+#[test]
+fn test_boundaries() {
+    let (_, client, _, first_id) = setup();
+
+    assert_eq!(client.add_from_first_contract(&first_id, &50, &-50), 0);
+    assert_eq!(client.sub_from_first_contract(&first_id, &50, &100), -50);
+    assert_eq!(
+        client.add_from_first_contract(&first_id, &(i32::MAX - 1), &1),
+        i32::MAX
+    );
+    assert_eq!(
+        client.sub_from_first_contract(&first_id, &50, &(i32::MIN + 51)),
+        i32::MAX
+    );
+}
+
+#[test]
+fn test_less_than() {
+    let (_, client, _, first_id) = setup();
+
+    assert_eq!(
+        client.try_add_from_first_contract(&first_id, &49, &2),
+        Err(Ok(ContractErrors::LessThan))
+    );
+    assert_eq!(
+        client.try_sub_from_first_contract(&first_id, &-1, &2),
+        Err(Ok(ContractErrors::LessThan))
+    );
+
+    // The guard runs before the callee is looked up
+    assert_eq!(
+        client.try_add_from_first_contract(&client.address, &0, &0),
+        Err(Ok(ContractErrors::LessThan))
+    );
+}
+
+#[test]
+fn test_callee_failure_is_returned() {
+    let (env, client, admin, first_id) = setup();
+
+    assert_eq!(
+        client.try_add_from_first_contract(&first_id, &i32::MAX, &1),
+        Err(Ok(ContractErrors::CalleeFailed))
+    );
+    assert_eq!(
+        client.try_sub_from_first_contract(&first_id, &50, &i32::MIN),
+        Err(Ok(ContractErrors::CalleeFailed))
+    );
+
+    // An allowed address that is not a contract at all
+    let account = Address::generate(&env);
+    client.allow(&admin, &account);
+    assert_eq!(
+        client.try_add_from_first_contract(&account, &60, &2),
+        Err(Ok(ContractErrors::CalleeFailed))
+    );
+
+    // The router itself has no `checked_add`
+    client.allow(&admin, &client.address);
+    assert_eq!(
+        client.try_add_from_first_contract(&client.address, &60, &2),
+        Err(Ok(ContractErrors::CalleeFailed))
+    );
+}
+
+#[test]
+fn test_allowlist() {
+    let (env, client, admin, first_id) = setup();
+    let stranger = Address::generate(&env);
+
+    assert_eq!(
+        client.try_add_from_first_contract(&stranger, &60, &2),
+        Err(Ok(ContractErrors::InvalidCallee))
+    );
+    assert_eq!(
+        client.try_allow(&stranger, &stranger),
+        Err(Ok(ContractErrors::Unauthorized))
+    );
+
+    client.disallow(&admin, &first_id);
+    assert!(!client.is_allowed(&first_id));
+    assert_eq!(
+        client.try_add_from_first_contract(&first_id, &60, &2),
+        Err(Ok(ContractErrors::InvalidCallee))
+    );
+}
+
+#[test]
+fn test_route() {
+    let (env, client, admin, first_id) = setup();
+
+    let args = vec![&env, 7_i32.into_val(&env), 5_i32.into_val(&env)];
+    let result: Val = client.route(&admin, &first_id, &symbol_short!("add"), &args);
+    assert_eq!(i32::try_from_val(&env, &result).unwrap(), 12);
+
+    // A trap, an error result and a missing function all come back the same way
+    let overflow = vec![&env, i32::MAX.into_val(&env), 1_i32.into_val(&env)];
+    assert_eq!(
+        client
+            .try_route(&admin, &first_id, &symbol_short!("add"), &overflow)
+            .err(),
+        Some(Ok(ContractErrors::CalleeFailed))
+    );
+    assert_eq!(
+        client
+            .try_route(
+                &admin,
+                &first_id,
+                &Symbol::new(&env, "checked_add"),
+                &overflow
+            )
+            .err(),
+        Some(Ok(ContractErrors::CalleeFailed))
+    );
+    assert_eq!(
+        client
+            .try_route(&admin, &first_id, &symbol_short!("missing"), &args)
+            .err(),
+        Some(Ok(ContractErrors::CalleeFailed))
+    );
+    assert_eq!(
+        client
+            .try_route(&admin, &client.address, &symbol_short!("add"), &args)
+            .err(),
+        Some(Ok(ContractErrors::InvalidCallee))
+    );
+}
+
+#[test]
+fn test_route_is_admin_only() {
+    let (env, client, admin, _) = setup();
+    let attacker = Address::generate(&env);
+
+    // The router holds tokens on an allowed token contract
+    let sac = env.register_stellar_asset_contract_v2(admin.clone());
+    let token = TokenClient::new(&env, &sac.address());
+    StellarAssetClient::new(&env, &sac.address()).mint(&client.address, &1000);
+    client.allow(&admin, &sac.address());
+
+    let args = vec![
+        &env,
+        client.address.into_val(&env),
+        attacker.into_val(&env),
+        1000_i128.into_val(&env),
+    ];
+    assert_eq!(
+        client
+            .try_route(&attacker, &sac.address(), &symbol_short!("transfer"), &args)
+            .err(),
+        Some(Ok(ContractErrors::Unauthorized))
+    );
+
+    // Naming the admin does not help without the admin's signature
+    env.set_auths(&[]);
+    assert!(client
+        .try_route(&admin, &sac.address(), &symbol_short!("transfer"), &args)
+        .is_err());
+
+    assert_eq!(token.balance(&attacker), 0);
+    assert_eq!(token.balance(&client.address), 1000);
+}
+
+mod v2 {
+    use crate::error::ContractErrors;
+    use crate::second_contract::SecondContract;
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    #[contract]
+    pub struct SecondContractV2;
+
+    #[contractimpl]
+    impl SecondContractV2 {
+        pub fn version(env: Env) -> u32 {
+            SecondContract::version(env)
+        }
+
+        pub fn migrate(env: Env, caller: Address) -> Result<u32, ContractErrors> {
+            upgradeable::migrate(&env, &caller, 2, SecondContract::migrate_from)
+                .map_err(ContractErrors::from)
+        }
+
+        pub fn is_allowed(env: Env, contract: Address) -> bool {
+            SecondContract::is_allowed(env, contract)
+        }
+
+        pub fn add_from_first_contract(
+            env: Env,
+            contract_address: Address,
+            a: i32,
+            b: i32,
+        ) -> Result<i32, ContractErrors> {
+            SecondContract::add_from_first_contract(env, contract_address, a, b)
+        }
+    }
+}
+
+#[test]
+fn test_upgrade_requires_admin() {
+    let (env, client, admin, _) = setup();
+    let stranger = Address::generate(&env);
+    let hash = BytesN::from_array(&env, &[0; 32]);
+
+    assert_eq!(client.version(), 1);
+    assert_eq!(
+        client.try_upgrade(&stranger, &hash),
+        Err(Ok(ContractErrors::Unauthorized))
+    );
+    assert_eq!(
+        client.try_migrate(&stranger),
+        Err(Ok(ContractErrors::Unauthorized))
+    );
+    assert_eq!(
+        client.try_migrate(&admin),
+        Err(Ok(ContractErrors::AlreadyMigrated))
+    );
+}
+
+#[test]
+fn test_upgrade_v1_to_v2_keeps_allowlist() {
+    let (env, client, admin, first_id) = setup();
+    let stranger = Address::generate(&env);
+
+    install_next_release(&env, &client.address, v2::SecondContractV2);
+    let client = v2::SecondContractV2Client::new(&env, &client.address);
+
+    assert_eq!(client.version(), 1);
+    assert!(client.is_allowed(&first_id));
+    assert_eq!(client.add_from_first_contract(&first_id, &60, &2), 62);
+
+    assert_eq!(
+        client.try_migrate(&stranger),
+        Err(Ok(ContractErrors::Unauthorized))
+    );
+    assert_eq!(client.migrate(&admin), 2);
+    assert_eq!(
+        client.try_migrate(&admin),
+        Err(Ok(ContractErrors::AlreadyMigrated))
+    );
+    assert_eq!(client.version(), 2);
+    assert!(client.is_allowed(&first_id));
 }
@@ -0,0 +1,87 @@
+//! Admin-gated upgrades shared by the contracts in this workspace.
+//!
+//! A contract records its admin and `VERSION` in its constructor with
+//! `init`, and exposes `version`, `upgrade` and `migrate` on top of the
+//! functions here. `VERSION` is bumped with every release that changes
+//! what is in storage, together with the matching migration step.
+#![no_std]
+
+use soroban_sdk::{contracttype, Address, BytesN, Env};
+
+pub mod testutils;
+
+#[contracttype]
+#[derive(Clone)]
+pub enum UpgradeKey {
+    Admin,
+    Version,
+}
+
+/// Each contract maps these into its own `#[contracterror]`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UpgradeError {
+    Unauthorized,
+    AlreadyMigrated,
+}
+
+/// Only meant for `__constructor`, which runs once at deployment, so there
+/// is no window in which someone else could claim the admin role.
+pub fn init(env: &Env, admin: &Address, version: u32) {
+    let storage = env.storage().instance();
+    storage.set(&UpgradeKey::Admin, admin);
+    storage.set(&UpgradeKey::Version, &version);
+}
+
+pub fn require_admin(env: &Env, caller: &Address) -> Result<(), UpgradeError> {
+    caller.require_auth();
+
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&UpgradeKey::Admin)
+        .expect("admin is set by the constructor");
+    if *caller != admin {
+        return Err(UpgradeError::Unauthorized);
+    }
+    Ok(())
+}
+
+/// The version whose storage layout is in place.
+pub fn version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&UpgradeKey::Version)
+        .unwrap_or(0)
+}
+
+/// Swaps the code of the running contract. Storage is kept as it is, the
+/// new code's `migrate` has to be called afterwards to bring it up to date.
+pub fn upgrade(env: &Env, caller: &Address, new_wasm_hash: BytesN<32>) -> Result<(), UpgradeError> {
+    require_admin(env, caller)?;
+
+    env.deployer().update_current_contract_wasm(new_wasm_hash);
+    Ok(())
+}
+
+/// Runs `step` once for every version between the stored one and
+/// `current`, oldest first, then records `current`. `step(env, version)`
+/// moves storage from the layout of `version` to that of `version + 1`.
+pub fn migrate(
+    env: &Env,
+    caller: &Address,
+    current: u32,
+    step: impl Fn(&Env, u32),
+) -> Result<u32, UpgradeError> {
+    require_admin(env, caller)?;
+
+    let stored = version(env);
+    if stored >= current {
+        return Err(UpgradeError::AlreadyMigrated);
+    }
+
+    for from in stored..current {
+        step(env, from);
+    }
+    env.storage().instance().set(&UpgradeKey::Version, &current);
+    Ok(current)
+}
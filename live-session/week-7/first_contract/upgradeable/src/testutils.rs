@@ -0,0 +1,37 @@
+#![cfg(any(test, feature = "testutils"))]
+
+extern crate std;
+
+use soroban_sdk::{
+    testutils::Register,
+    xdr::{Limits, ScEnvMetaEntry, ScEnvMetaEntryInterfaceVersion, WriteXdr},
+    Address, Bytes, Env,
+};
+
+/// Replaces the code at `contract_id` with `next_release`, keeping its
+/// storage. Native contracts have no wasm hash, so this stands in for
+/// `upgrade` when the next release is a native contract in the tests.
+pub fn install_next_release<C: Register>(env: &Env, contract_id: &Address, next_release: C) {
+    env.register_at(contract_id, next_release, ());
+}
+
+/// A wasm module with nothing in it but the interface version the host
+/// checks on upload, for exercising `upgrade` itself.
+pub fn empty_wasm(env: &Env) -> Bytes {
+    let meta = ScEnvMetaEntry::ScEnvMetaKindInterfaceVersion(ScEnvMetaEntryInterfaceVersion {
+        protocol: env.ledger().protocol_version(),
+        pre_release: 0,
+    })
+    .to_xdr(Limits::none())
+    .unwrap();
+    let name = b"contractenvmetav0";
+
+    let mut wasm = std::vec![0x00, b'a', b's', b'm', 1, 0, 0, 0];
+    wasm.push(0);
+    wasm.push((1 + name.len() + meta.len()) as u8);
+    wasm.push(name.len() as u8);
+    wasm.extend_from_slice(name);
+    wasm.extend_from_slice(&meta);
+
+    Bytes::from_slice(env, &wasm)
+}
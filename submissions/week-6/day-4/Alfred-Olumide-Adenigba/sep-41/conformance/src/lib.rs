@@ -0,0 +1,492 @@
+//! Checks any token contract against the SEP-41 interface by driving it
+//! through `soroban_sdk::token::TokenClient`, so tokens are judged on the
+//! standard function names and semantics rather than their own.
+//!
+//! Register and initialize the token in a test, make sure `admin` holds at
+//! least `MIN_BALANCE`, then:
+//!
+//! ```ignore
+//! sep41_conformance::Sep41::new(&env, &contract_id, &admin).run_all();
+//! ```
+//!
+//! Every check funds fresh accounts from `admin`, so they can run in any
+//! order. Failures panic with the name of the rule that was broken.
+
+mod test;
+
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, Events, Ledger},
+    token::TokenClient,
+    Address, Env, IntoVal, Symbol, Val, Vec,
+};
+
+/// What `admin` must hold for `run_all` to have enough to hand out.
+pub const MIN_BALANCE: i128 = 20_000;
+
+const FUNDING: i128 = 1_000;
+
+pub struct Sep41<'a> {
+    env: Env,
+    token: TokenClient<'a>,
+    admin: Address,
+}
+
+impl<'a> Sep41<'a> {
+    /// Mocks all auths on `env`; the auth checks turn mocking off and back
+    /// on as they go.
+    pub fn new(env: &Env, token: &Address, admin: &Address) -> Self {
+        env.mock_all_auths();
+
+        let suite = Self {
+            env: env.clone(),
+            token: TokenClient::new(env, token),
+            admin: admin.clone(),
+        };
+        assert!(
+            suite.token.balance(admin) >= MIN_BALANCE,
+            "admin must hold at least {} tokens",
+            MIN_BALANCE
+        );
+        suite
+    }
+
+    pub fn run_all(&self) {
+        self.check_metadata();
+        self.check_transfer();
+        self.check_transfer_insufficient_balance();
+        self.check_approve();
+        self.check_transfer_from();
+        self.check_transfer_from_over_allowance();
+        self.check_allowance_expiry();
+        self.check_burn();
+        self.check_burn_from();
+        self.check_negative_amounts();
+        self.check_events();
+        self.check_auth();
+    }
+
+    pub fn check_metadata(&self) {
+        let name = self.token.name();
+        let symbol = self.token.symbol();
+
+        assert!(!name.is_empty(), "name must not be empty");
+        assert!(!symbol.is_empty(), "symbol must not be empty");
+        assert!(self.token.decimals() <= 18, "decimals must be at most 18");
+    }
+
+    pub fn check_transfer(&self) {
+        let from = self.fund(FUNDING);
+        let to = Address::generate(&self.env);
+
+        assert_eq!(self.token.balance(&to), 0, "unknown accounts hold 0");
+
+        self.token.transfer(&from, &to, &300);
+        assert_eq!(self.token.balance(&from), FUNDING - 300, "transfer debits");
+        assert_eq!(self.token.balance(&to), 300, "transfer credits");
+
+        self.token.transfer(&from, &to, &0);
+        assert_eq!(self.token.balance(&to), 300, "zero transfer is a no-op");
+
+        self.token.transfer(&from, &from, &100);
+        assert_eq!(
+            self.token.balance(&from),
+            FUNDING - 300,
+            "self transfer keeps the balance"
+        );
+    }
+
+    pub fn check_transfer_insufficient_balance(&self) {
+        let from = self.fund(FUNDING);
+        let to = Address::generate(&self.env);
+
+        assert!(
+            self.token.try_transfer(&from, &to, &(FUNDING + 1)).is_err(),
+            "transfer over balance must fail"
+        );
+        assert_eq!(self.token.balance(&from), FUNDING, "failed transfer debits");
+        assert_eq!(self.token.balance(&to), 0, "failed transfer credits");
+    }
+
+    pub fn check_approve(&self) {
+        let from = self.fund(FUNDING);
+        let spender = Address::generate(&self.env);
+        let expiration = self.env.ledger().sequence() + 100;
+
+        assert_eq!(
+            self.token.allowance(&from, &spender),
+            0,
+            "no allowance by default"
+        );
+
+        self.token.approve(&from, &spender, &500, &expiration);
+        assert_eq!(self.token.allowance(&from, &spender), 500, "approve sets");
+
+        self.token.approve(&from, &spender, &200, &expiration);
+        assert_eq!(
+            self.token.allowance(&from, &spender),
+            200,
+            "approve replaces"
+        );
+
+        self.token.approve(&from, &spender, &0, &expiration);
+        assert_eq!(self.token.allowance(&from, &spender), 0, "approve 0 clears");
+        assert_eq!(
+            self.token.balance(&from),
+            FUNDING,
+            "approve moves no tokens"
+        );
+    }
+
+    pub fn check_transfer_from(&self) {
+        let from = self.fund(FUNDING);
+        let spender = Address::generate(&self.env);
+        let to = Address::generate(&self.env);
+        let expiration = self.env.ledger().sequence() + 100;
+
+        self.token.approve(&from, &spender, &500, &expiration);
+        self.token.transfer_from(&spender, &from, &to, &300);
+
+        assert_eq!(
+            self.token.balance(&from),
+            FUNDING - 300,
+            "transfer_from debits"
+        );
+        assert_eq!(self.token.balance(&to), 300, "transfer_from credits");
+        assert_eq!(self.token.balance(&spender), 0, "spender receives nothing");
+        assert_eq!(
+            self.token.allowance(&from, &spender),
+            200,
+            "transfer_from spends the allowance"
+        );
+    }
+
+    pub fn check_transfer_from_over_allowance(&self) {
+        let from = self.fund(FUNDING);
+        let spender = Address::generate(&self.env);
+        let to = Address::generate(&self.env);
+        let expiration = self.env.ledger().sequence() + 100;
+
+        assert!(
+            self.token
+                .try_transfer_from(&spender, &from, &to, &1)
+                .is_err(),
+            "transfer_from without allowance must fail"
+        );
+
+        self.token.approve(&from, &spender, &100, &expiration);
+        assert!(
+            self.token
+                .try_transfer_from(&spender, &from, &to, &101)
+                .is_err(),
+            "transfer_from over allowance must fail"
+        );
+
+        self.token
+            .approve(&from, &spender, &(FUNDING + 1), &expiration);
+        assert!(
+            self.token
+                .try_transfer_from(&spender, &from, &to, &(FUNDING + 1))
+                .is_err(),
+            "transfer_from over balance must fail"
+        );
+        assert_eq!(
+            self.token.balance(&from),
+            FUNDING,
+            "failed transfer_from debits"
+        );
+        assert_eq!(
+            self.token.allowance(&from, &spender),
+            FUNDING + 1,
+            "failed transfer_from spends the allowance"
+        );
+    }
+
+    /// Moves the ledger forward past the expiration and back again.
+    pub fn check_allowance_expiry(&self) {
+        let from = self.fund(FUNDING);
+        let spender = Address::generate(&self.env);
+        let to = Address::generate(&self.env);
+        let start = self.env.ledger().sequence();
+
+        self.token.approve(&from, &spender, &100, &(start + 10));
+
+        self.env.ledger().set_sequence_number(start + 10);
+        assert_eq!(
+            self.token.allowance(&from, &spender),
+            100,
+            "allowance holds through the expiration ledger"
+        );
+
+        self.env.ledger().set_sequence_number(start + 11);
+        assert_eq!(
+            self.token.allowance(&from, &spender),
+            0,
+            "allowance is 0 after the expiration ledger"
+        );
+        assert!(
+            self.token
+                .try_transfer_from(&spender, &from, &to, &1)
+                .is_err(),
+            "expired allowance cannot be spent"
+        );
+        assert!(
+            self.token
+                .try_approve(&from, &spender, &100, &(start + 10))
+                .is_err(),
+            "approving an amount with a past expiration must fail"
+        );
+
+        self.env.ledger().set_sequence_number(start);
+    }
+
+    pub fn check_burn(&self) {
+        let from = self.fund(FUNDING);
+
+        self.token.burn(&from, &400);
+        assert_eq!(self.token.balance(&from), FUNDING - 400, "burn debits");
+
+        assert!(
+            self.token.try_burn(&from, &(FUNDING - 399)).is_err(),
+            "burn over balance must fail"
+        );
+        assert_eq!(
+            self.token.balance(&from),
+            FUNDING - 400,
+            "failed burn debits"
+        );
+    }
+
+    pub fn check_burn_from(&self) {
+        let from = self.fund(FUNDING);
+        let spender = Address::generate(&self.env);
+        let expiration = self.env.ledger().sequence() + 100;
+
+        assert!(
+            self.token.try_burn_from(&spender, &from, &1).is_err(),
+            "burn_from without allowance must fail"
+        );
+
+        self.token.approve(&from, &spender, &500, &expiration);
+        self.token.burn_from(&spender, &from, &300);
+        assert_eq!(self.token.balance(&from), FUNDING - 300, "burn_from debits");
+        assert_eq!(
+            self.token.allowance(&from, &spender),
+            200,
+            "burn_from spends the allowance"
+        );
+
+        assert!(
+            self.token.try_burn_from(&spender, &from, &201).is_err(),
+            "burn_from over allowance must fail"
+        );
+    }
+
+    pub fn check_negative_amounts(&self) {
+        let from = self.fund(FUNDING);
+        let spender = Address::generate(&self.env);
+        let to = Address::generate(&self.env);
+        let expiration = self.env.ledger().sequence() + 100;
+        self.token.approve(&from, &spender, &100, &expiration);
+
+        assert!(
+            self.token.try_transfer(&from, &to, &-1).is_err(),
+            "negative transfer must fail"
+        );
+        assert!(
+            self.token
+                .try_transfer_from(&spender, &from, &to, &-1)
+                .is_err(),
+            "negative transfer_from must fail"
+        );
+        assert!(
+            self.token
+                .try_approve(&from, &spender, &-1, &expiration)
+                .is_err(),
+            "negative approve must fail"
+        );
+        assert!(
+            self.token.try_burn(&from, &-1).is_err(),
+            "negative burn must fail"
+        );
+        assert!(
+            self.token.try_burn_from(&spender, &from, &-1).is_err(),
+            "negative burn_from must fail"
+        );
+        assert_eq!(
+            self.token.balance(&from),
+            FUNDING,
+            "negative amount moved funds"
+        );
+        assert_eq!(self.token.balance(&to), 0, "negative amount moved funds");
+        assert_eq!(
+            self.token.allowance(&from, &spender),
+            100,
+            "negative amount changed the allowance"
+        );
+    }
+
+    /// SEP-41 fixes the first topics of each event; tokens may append more,
+    /// as the Stellar Asset Contract appends the asset name.
+    pub fn check_events(&self) {
+        let from = self.fund(FUNDING);
+        let to = Address::generate(&self.env);
+        let expiration = self.env.ledger().sequence() + 100;
+
+        self.token.transfer(&from, &to, &10);
+        self.assert_event(
+            "transfer",
+            (from.clone(), to.clone()).into_val(&self.env),
+            10_i128.into_val(&self.env),
+        );
+
+        self.token.approve(&from, &to, &20, &expiration);
+        self.assert_event(
+            "approve",
+            (from.clone(), to.clone()).into_val(&self.env),
+            (20_i128, expiration).into_val(&self.env),
+        );
+
+        self.token.transfer_from(&to, &from, &to, &5);
+        self.assert_event(
+            "transfer",
+            (from.clone(), to.clone()).into_val(&self.env),
+            5_i128.into_val(&self.env),
+        );
+
+        self.token.burn(&from, &30);
+        self.assert_event(
+            "burn",
+            (from.clone(),).into_val(&self.env),
+            30_i128.into_val(&self.env),
+        );
+
+        self.token.burn_from(&to, &from, &5);
+        self.assert_event(
+            "burn",
+            (from.clone(),).into_val(&self.env),
+            5_i128.into_val(&self.env),
+        );
+    }
+
+    /// Each state changing function must be authorized by the account whose
+    /// tokens or allowance it uses, and fail without it.
+    pub fn check_auth(&self) {
+        let from = self.fund(FUNDING);
+        let spender = Address::generate(&self.env);
+        let to = Address::generate(&self.env);
+        let expiration = self.env.ledger().sequence() + 100;
+
+        self.token.transfer(&from, &to, &10);
+        self.assert_auth(&from, "transfer", (&from, &to, 10_i128).into_val(&self.env));
+
+        self.token.approve(&from, &spender, &100, &expiration);
+        self.assert_auth(
+            &from,
+            "approve",
+            (&from, &spender, 100_i128, expiration).into_val(&self.env),
+        );
+
+        self.token.transfer_from(&spender, &from, &to, &10);
+        self.assert_auth(
+            &spender,
+            "transfer_from",
+            (&spender, &from, &to, 10_i128).into_val(&self.env),
+        );
+
+        self.token.burn(&from, &10);
+        self.assert_auth(&from, "burn", (&from, 10_i128).into_val(&self.env));
+
+        self.token.burn_from(&spender, &from, &10);
+        self.assert_auth(
+            &spender,
+            "burn_from",
+            (&spender, &from, 10_i128).into_val(&self.env),
+        );
+
+        self.env.set_auths(&[]);
+        assert!(
+            self.token.try_transfer(&from, &to, &1).is_err(),
+            "transfer without auth must fail"
+        );
+        assert!(
+            self.token
+                .try_approve(&from, &spender, &1, &expiration)
+                .is_err(),
+            "approve without auth must fail"
+        );
+        assert!(
+            self.token
+                .try_transfer_from(&spender, &from, &to, &1)
+                .is_err(),
+            "transfer_from without auth must fail"
+        );
+        assert!(
+            self.token.try_burn(&from, &1).is_err(),
+            "burn without auth must fail"
+        );
+        assert!(
+            self.token.try_burn_from(&spender, &from, &1).is_err(),
+            "burn_from without auth must fail"
+        );
+        self.env.mock_all_auths();
+    }
+
+    // A new account holding `amount`, paid for by the admin
+    fn fund(&self, amount: i128) -> Address {
+        let account = Address::generate(&self.env);
+        self.token.transfer(&self.admin, &account, &amount);
+        account
+    }
+
+    // The last call must have published one event whose topics start with
+    // `name` and `topics`, carrying `data`
+    fn assert_event(&self, name: &str, topics: Vec<Val>, data: Val) {
+        let events: std::vec::Vec<_> = self
+            .env
+            .events()
+            .all()
+            .iter()
+            .filter(|(contract, _, _)| *contract == self.token.address)
+            .collect();
+        assert_eq!(events.len(), 1, "{} must publish one event", name);
+        let (_, event_topics, event_data) = &events[0];
+
+        let mut expected = Vec::from_array(
+            &self.env,
+            [Symbol::new(&self.env, name).into_val(&self.env)],
+        );
+        expected.append(&topics);
+        let end = expected.len().min(event_topics.len());
+        assert_eq!(
+            event_topics.slice(0..end),
+            expected,
+            "{} event topics",
+            name
+        );
+        assert_eq!(
+            Vec::from_array(&self.env, [*event_data]),
+            Vec::from_array(&self.env, [data]),
+            "{} event data",
+            name
+        );
+    }
+
+    // The last call must have required exactly `signer`'s auth for itself
+    fn assert_auth(&self, signer: &Address, function: &str, args: Vec<Val>) {
+        let auths = self.env.auths();
+        assert_eq!(auths.len(), 1, "{} must require one signer", function);
+
+        let (address, invocation) = &auths[0];
+        assert_eq!(address, signer, "{} signer", function);
+        assert_eq!(
+            invocation.function,
+            AuthorizedFunction::Contract((
+                self.token.address.clone(),
+                Symbol::new(&self.env, function),
+                args,
+            )),
+            "{} authorized arguments",
+            function
+        );
+    }
+}
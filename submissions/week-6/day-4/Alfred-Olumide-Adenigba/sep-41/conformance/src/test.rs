@@ -0,0 +1,19 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::Address as _, token::StellarAssetClient, Address, Env};
+
+use crate::{Sep41, MIN_BALANCE};
+
+// The Stellar Asset Contract is the reference implementation, so the suite
+// has to pass against it before it can judge anything else
+#[test]
+fn test_stellar_asset_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let sac = env.register_stellar_asset_contract_v2(admin.clone());
+    StellarAssetClient::new(&env, &sac.address()).mint(&admin, &MIN_BALANCE);
+
+    Sep41::new(&env, &sac.address(), &admin).run_all();
+}
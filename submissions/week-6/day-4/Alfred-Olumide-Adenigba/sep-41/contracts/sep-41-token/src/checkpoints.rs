@@ -0,0 +1,55 @@
+use soroban_sdk::Env;
+
+use crate::storage::{self, Checkpoint, CheckpointSeries};
+
+// Records `value` as of the current ledger. Several writes in the same
+// ledger keep only the last one.
+pub fn push(env: &Env, series: &CheckpointSeries, value: i128) {
+    let ledger = env.ledger().sequence();
+    let count = storage::get_checkpoint_count(env, series);
+    let checkpoint = Checkpoint { ledger, value };
+
+    if count > 0 && storage::get_checkpoint(env, series, count - 1).ledger == ledger {
+        storage::set_checkpoint(env, series, count - 1, &checkpoint);
+    } else {
+        storage::set_checkpoint(env, series, count, &checkpoint);
+        storage::set_checkpoint_count(env, series, count + 1);
+    }
+}
+
+pub fn latest(env: &Env, series: &CheckpointSeries) -> i128 {
+    match storage::get_checkpoint_count(env, series) {
+        0 => 0,
+        count => storage::get_checkpoint(env, series, count - 1).value,
+    }
+}
+
+// The value of the last checkpoint at or before `ledger`, or 0 if the
+// series starts later. Lookups at or after the latest checkpoint only read
+// that entry, so they keep working once older history has been archived;
+// searching further back needs those entries live (or restored).
+pub fn at(env: &Env, series: &CheckpointSeries, ledger: u32) -> i128 {
+    let count = storage::get_checkpoint_count(env, series);
+    if count == 0 {
+        return 0;
+    }
+    let last = storage::get_checkpoint(env, series, count - 1);
+    if last.ledger <= ledger {
+        return last.value;
+    }
+
+    let (mut low, mut high) = (0, count - 1);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if storage::get_checkpoint(env, series, mid).ledger > ledger {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+
+    match low {
+        0 => 0,
+        index => storage::get_checkpoint(env, series, index - 1).value,
+    }
+}
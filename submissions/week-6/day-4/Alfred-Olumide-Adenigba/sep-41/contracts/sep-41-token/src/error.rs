@@ -0,0 +1,27 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum TokenError {
+    AlreadyInitialized = 1,
+    InvalidDecimals = 2,
+    InvalidSymbol = 3,
+    InvalidCap = 4,
+    Paused = 5,
+    NotPaused = 6,
+    AccountFrozen = 7,
+    NegativeAmount = 8,
+    Overflow = 9,
+    InsufficientBalance = 10,
+    InsufficientAllowance = 11,
+    NotMinter = 12,
+    MinterAllowanceExceeded = 13,
+    SupplyCapExceeded = 14,
+    ExpirationInPast = 15,
+    FutureLedger = 16,
+    InvalidFee = 17,
+    InvalidNonce = 18,
+    NoPermitKey = 19,
+    ExpirationTooFar = 20,
+}
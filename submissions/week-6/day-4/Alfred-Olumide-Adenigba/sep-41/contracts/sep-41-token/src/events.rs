@@ -5,7 +5,88 @@ pub fn transfer_event(env: &Env, from: Address, to: Address, amount: i128) {
     env.events().publish(topics, amount);
 }
 
-pub fn approve_event(env: &Env, owner: Address, spender: Address, amount: i128) {
-    let topics = (symbol_short!("approve"), owner.clone(), spender.clone());
+pub fn burn_event(env: &Env, from: Address, amount: i128) {
+    let topics = (symbol_short!("burn"), from);
+    env.events().publish(topics, amount);
+}
+
+pub fn mint_event(env: &Env, minter: Address, to: Address, amount: i128) {
+    let topics = (symbol_short!("mint"), minter, to);
+    env.events().publish(topics, amount);
+}
+
+pub fn minter_granted_event(env: &Env, admin: Address, minter: Address, allowance: i128) {
+    let topics = (symbol_short!("grant"), admin, minter);
+    env.events().publish(topics, allowance);
+}
+
+pub fn minter_revoked_event(env: &Env, admin: Address, minter: Address) {
+    let topics = (symbol_short!("revoke"), admin, minter);
+    env.events().publish(topics, ());
+}
+
+pub fn paused_event(env: &Env, admin: Address) {
+    let topics = (symbol_short!("paused"), admin);
+    env.events().publish(topics, ());
+}
+
+pub fn unpaused_event(env: &Env, admin: Address) {
+    let topics = (symbol_short!("unpaused"), admin);
+    env.events().publish(topics, ());
+}
+
+pub fn freeze_event(env: &Env, admin: Address, account: Address) {
+    let topics = (symbol_short!("freeze"), admin, account);
+    env.events().publish(topics, ());
+}
+
+pub fn unfreeze_event(env: &Env, admin: Address, account: Address) {
+    let topics = (symbol_short!("unfreeze"), admin, account);
+    env.events().publish(topics, ());
+}
+
+pub fn clawback_event(env: &Env, admin: Address, from: Address, amount: i128) {
+    let topics = (symbol_short!("clawback"), admin, from);
     env.events().publish(topics, amount);
 }
+
+pub fn delegate_changed_event(
+    env: &Env,
+    delegator: Address,
+    from: Option<Address>,
+    to: Address,
+) {
+    let topics = (symbol_short!("delegate"), delegator);
+    env.events().publish(topics, (from, to));
+}
+
+pub fn votes_changed_event(env: &Env, delegate: Address, previous: i128, votes: i128) {
+    let topics = (symbol_short!("votes"), delegate);
+    env.events().publish(topics, (previous, votes));
+}
+
+pub fn fee_event(env: &Env, from: Address, treasury: Address, fee: i128) {
+    let topics = (symbol_short!("fee"), from, treasury);
+    env.events().publish(topics, fee);
+}
+
+pub fn fee_set_event(env: &Env, admin: Address, fee_bps: u32, treasury: Address) {
+    let topics = (symbol_short!("fee_set"), admin);
+    env.events().publish(topics, (fee_bps, treasury));
+}
+
+pub fn fee_exempt_event(env: &Env, admin: Address, account: Address, exempt: bool) {
+    let topics = (symbol_short!("exempt"), admin, account);
+    env.events().publish(topics, exempt);
+}
+
+pub fn approve_event(
+    env: &Env,
+    owner: Address,
+    spender: Address,
+    amount: i128,
+    expiration_ledger: u32,
+) {
+    let topics = (symbol_short!("approve"), owner.clone(), spender.clone());
+    env.events().publish(topics, (amount, expiration_ledger));
+}
@@ -1,31 +1,194 @@
-use soroban_sdk::{Address, Env, String};
-use crate::{token::TokenTrait, storage, events};
+use soroban_sdk::{panic_with_error, Address, BytesN, Env, String};
+use crate::{token::TokenTrait, storage, events, checkpoints, permit};
+use crate::error::TokenError;
+use crate::storage::{CheckpointSeries, FeeConfig, TokenMetadata};
+
+pub const MAX_DECIMALS: u32 = 18;
+pub const MAX_SYMBOL_LEN: u32 = 12;
+/// 10%, so a misconfigured fee can never swallow a transfer.
+pub const MAX_FEE_BPS: u32 = 1_000;
+const BPS_DENOMINATOR: i128 = 10_000;
 
 pub struct Token;
 
+fn require_not_paused(env: &Env) {
+    if storage::is_paused(env) {
+        panic_with_error!(env, TokenError::Paused);
+    }
+}
+
+fn require_not_frozen(env: &Env, account: &Address) {
+    if storage::is_frozen(env, account) {
+        panic_with_error!(env, TokenError::AccountFrozen);
+    }
+}
+
+// A negative amount would run every debit below in reverse
+fn require_nonnegative(env: &Env, amount: i128) {
+    if amount < 0 {
+        panic_with_error!(env, TokenError::NegativeAmount);
+    }
+}
+
+fn checked(env: &Env, value: Option<i128>) -> i128 {
+    match value {
+        Some(value) => value,
+        None => panic_with_error!(env, TokenError::Overflow),
+    }
+}
+
+fn write_balance(env: &Env, id: &Address, balance: i128) {
+    storage::set_balance(env, id, balance);
+    checkpoints::push(env, &CheckpointSeries::Balance(id.clone()), balance);
+}
+
+fn write_total_supply(env: &Env, total_supply: i128) {
+    storage::set_total_supply(env, total_supply);
+    checkpoints::push(env, &CheckpointSeries::TotalSupply, total_supply);
+}
+
+fn add_votes(env: &Env, delegate: &Address, amount: i128) {
+    let series = CheckpointSeries::Votes(delegate.clone());
+    let previous = checkpoints::latest(env, &series);
+    let votes = checked(env, previous.checked_add(amount));
+    checkpoints::push(env, &series, votes);
+    events::votes_changed_event(env, delegate.clone(), previous, votes);
+}
+
+// Votes follow the balance from one delegate to the other
+fn move_votes(env: &Env, from: Option<Address>, to: Option<Address>, amount: i128) {
+    if from == to || amount == 0 {
+        return;
+    }
+    if let Some(from) = from {
+        add_votes(env, &from, checked(env, 0_i128.checked_sub(amount)));
+    }
+    if let Some(to) = to {
+        add_votes(env, &to, amount);
+    }
+}
+
+fn spend_balance(env: &Env, from: &Address, amount: i128) {
+    let balance = storage::get_balance(env, from);
+    if balance < amount {
+        panic_with_error!(env, TokenError::InsufficientBalance);
+    }
+    write_balance(env, from, checked(env, balance.checked_sub(amount)));
+    move_votes(env, storage::get_delegate(env, from), None, amount);
+}
+
+// Reads the balance after any debit, so a transfer to oneself nets out
+fn receive_balance(env: &Env, to: &Address, amount: i128) {
+    let balance = storage::get_balance(env, to);
+    write_balance(env, to, checked(env, balance.checked_add(amount)));
+    move_votes(env, None, storage::get_delegate(env, to), amount);
+}
+
+fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) {
+    let allowance = storage::read_allowance(env, from, spender);
+    if allowance.amount < amount {
+        panic_with_error!(env, TokenError::InsufficientAllowance);
+    }
+    storage::set_allowance(
+        env,
+        from,
+        spender,
+        checked(env, allowance.amount.checked_sub(amount)),
+        allowance.expiration_ledger,
+    );
+}
+
+// Moves `amount` out of `from`, less the transfer fee which goes to the
+// treasury. No fee is taken if `from`, `to` or the spender moving the
+// tokens is exempt. Returns what `to` received.
+fn transfer_with_fee(
+    env: &Env,
+    spender: Option<&Address>,
+    from: &Address,
+    to: &Address,
+    amount: i128,
+) -> i128 {
+    spend_balance(env, from, amount);
+
+    let exempt = storage::is_fee_exempt(env, from)
+        || storage::is_fee_exempt(env, to)
+        || spender.is_some_and(|spender| storage::is_fee_exempt(env, spender));
+    let fee = match storage::get_fee(env) {
+        Some(config) if !exempt => {
+            let fee = checked(env, amount.checked_mul(config.fee_bps as i128)) / BPS_DENOMINATOR;
+            if fee > 0 {
+                // The treasury may have been frozen since `set_fee`
+                require_not_frozen(env, &config.treasury);
+                receive_balance(env, &config.treasury, fee);
+                events::fee_event(env, from.clone(), config.treasury, fee);
+            }
+            fee
+        }
+        _ => 0,
+    };
+
+    let received = amount - fee;
+    receive_balance(env, to, received);
+    received
+}
+
+fn decrease_supply(env: &Env, amount: i128) {
+    let total_supply = storage::get_total_supply(env);
+    write_total_supply(env, checked(env, total_supply.checked_sub(amount)));
+}
+
 impl TokenTrait for Token {
-    fn initialize(env: Env, admin: Address, total_supply: i128) {
+    fn __constructor(
+        env: Env,
+        admin: Address,
+        total_supply: i128,
+        cap: Option<i128>,
+        metadata: TokenMetadata,
+    ) -> Result<(), TokenError> {
+        if total_supply < 0 {
+            return Err(TokenError::NegativeAmount);
+        }
+        if metadata.decimals > MAX_DECIMALS {
+            return Err(TokenError::InvalidDecimals);
+        }
+        if metadata.symbol.is_empty() || metadata.symbol.len() > MAX_SYMBOL_LEN {
+            return Err(TokenError::InvalidSymbol);
+        }
+
+        if let Some(cap) = cap {
+            if cap < total_supply {
+                return Err(TokenError::InvalidCap);
+            }
+            storage::set_cap(&env, cap);
+        }
+
         storage::set_admin(&env, &admin);
-        storage::set_total_supply(&env, total_supply);
-        storage::set_balance(&env, &admin, total_supply);
+        storage::set_metadata(&env, &metadata);
+        write_total_supply(&env, total_supply);
+        write_balance(&env, &admin, total_supply);
+        Ok(())
     }
 
     fn name(env: Env) -> String {
-        String::from_str(&env, "MyToken")
+        storage::get_metadata(&env).name
     }
 
     fn symbol(env: Env) -> String {
-        String::from_str(&env, "MTK")
+        storage::get_metadata(&env).symbol
     }
 
-    fn decimals(_env: Env) -> u32 {
-        18
-    } 
+    fn decimals(env: Env) -> u32 {
+        storage::get_metadata(&env).decimals
+    }
 
     fn total_supply(env: Env) -> i128 {
         storage::get_total_supply(&env)
     }
 
+    fn balance(env: Env, id: Address) -> i128 {
+        storage::get_balance(&env, &id)
+    }
+
     fn balance_of(env: Env, owner: Address) -> i128 {
         storage::get_balance(&env, &owner)
     }
@@ -34,94 +197,298 @@ impl TokenTrait for Token {
         storage::get_allowance(&env, &owner, &spender)
     }
 
-    fn approve(env: Env, from: Address, spender: Address, amount: i128) {
+    fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
         from.require_auth();
-        storage::set_allowance(&env, &from, &spender, amount);
-        events::approve_event(&env, from, spender, amount);
+        require_nonnegative(&env, amount);
+        storage::set_allowance(&env, &from, &spender, amount, expiration_ledger);
+        events::approve_event(&env, from, spender, amount, expiration_ledger);
+    }
+
+    fn set_permit_key(env: Env, owner: Address, key: Option<BytesN<32>>) {
+        owner.require_auth();
+        storage::set_permit_key(&env, &owner, key);
+    }
+
+    fn permit_key(env: Env, owner: Address) -> Option<BytesN<32>> {
+        storage::get_permit_key(&env, &owner)
+    }
+
+    fn permit(
+        env: Env,
+        owner: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+        nonce: u64,
+        signature: BytesN<64>,
+    ) -> Result<(), TokenError> {
+        require_nonnegative(&env, amount);
+        let key = storage::get_permit_key(&env, &owner).ok_or(TokenError::NoPermitKey)?;
+        let expected = storage::get_permit_nonce(&env, &owner);
+        if nonce != expected {
+            return Err(TokenError::InvalidNonce);
+        }
+
+        // Traps if the signature does not match
+        let payload = permit::payload(
+            &env,
+            &env.current_contract_address(),
+            &owner,
+            &spender,
+            amount,
+            expiration_ledger,
+            nonce,
+        );
+        env.crypto().ed25519_verify(&key, &payload, &signature);
+
+        storage::set_permit_nonce(&env, &owner, expected + 1);
+        storage::set_allowance(&env, &owner, &spender, amount, expiration_ledger);
+        events::approve_event(&env, owner, spender, amount, expiration_ledger);
+        Ok(())
+    }
+
+    fn permit_nonce(env: Env, owner: Address) -> u64 {
+        storage::get_permit_nonce(&env, &owner)
     }
 
     fn transfer(env: Env, from: Address, to: Address, amount: i128) {
         from.require_auth();
-        let mut balance_from = storage::get_balance(&env, &from);
-        assert!(balance_from >= amount, "Insufficient balance");
+        require_nonnegative(&env, amount);
+        require_not_paused(&env);
+        require_not_frozen(&env, &from);
+        require_not_frozen(&env, &to);
 
-        balance_from -= amount;
-        let mut balance_to = storage::get_balance(&env, &to);
-        balance_to += amount;
+        let received = transfer_with_fee(&env, None, &from, &to, amount);
 
-        storage::set_balance(&env, &from, balance_from);
-        storage::set_balance(&env, &to, balance_to);
-
-        events::transfer_event(&env, from, to, amount);
+        events::transfer_event(&env, from, to, received);
     }
 
     fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
         spender.require_auth();
-        let mut allowance = storage::get_allowance(&env, &from, &spender);
-        assert!(allowance >= amount, "Allowance exceeded");
+        require_nonnegative(&env, amount);
+        require_not_paused(&env);
+        require_not_frozen(&env, &spender);
+        require_not_frozen(&env, &from);
+        require_not_frozen(&env, &to);
 
-        let mut balance_from = storage::get_balance(&env, &from);
-        assert!(balance_from >= amount, "Insufficient balance");
+        spend_allowance(&env, &from, &spender, amount);
+        let received = transfer_with_fee(&env, Some(&spender), &from, &to, amount);
 
-        balance_from -= amount;
-        allowance -= amount;
+        events::transfer_event(&env, from, to, received);
+    }
 
-        let mut balance_to = storage::get_balance(&env, &to);
-        balance_to += amount;
+    fn mint(env: Env, minter: Address, to: Address, amount: i128) {
+        minter.require_auth();
+        require_nonnegative(&env, amount);
+        require_not_paused(&env);
+        require_not_frozen(&env, &minter);
+        require_not_frozen(&env, &to);
+
+        // The admin mints freely, anyone else spends down their allowance
+        if minter != storage::get_admin(&env) {
+            let allowance = match storage::get_minter_allowance(&env, &minter) {
+                Some(allowance) => allowance,
+                None => panic_with_error!(&env, TokenError::NotMinter),
+            };
+            if allowance < amount {
+                panic_with_error!(&env, TokenError::MinterAllowanceExceeded);
+            }
+            storage::set_minter_allowance(&env, &minter, checked(&env, allowance.checked_sub(amount)));
+        }
+
+        let total_supply = checked(&env, storage::get_total_supply(&env).checked_add(amount));
+        if let Some(cap) = storage::get_cap(&env) {
+            if total_supply > cap {
+                panic_with_error!(&env, TokenError::SupplyCapExceeded);
+            }
+        }
+
+        write_total_supply(&env, total_supply);
+        receive_balance(&env, &to, amount);
+
+        events::mint_event(&env, minter, to, amount);
+    }
 
-        storage::set_balance(&env, &from, balance_from);
-        storage::set_balance(&env, &to, balance_to);
-        storage::set_allowance(&env, &from, &spender, allowance);
+    fn burn(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        require_nonnegative(&env, amount);
+        require_not_paused(&env);
+        require_not_frozen(&env, &from);
 
-        events::transfer_event(&env, from, to, amount);
+        spend_balance(&env, &from, amount);
+        decrease_supply(&env, amount);
+
+        events::burn_event(&env, from, amount);
     }
 
-    fn mint(env: Env, to: Address, amount: i128) {
-        let admin = env.current_contract_address();
-        let mut total_supply = Self::total_supply(env.clone());
-        total_supply += amount;
+    fn burn_from(env: Env, spender: Address, from: Address, amount: i128) {
+        spender.require_auth();
+        require_nonnegative(&env, amount);
+        require_not_paused(&env);
+        require_not_frozen(&env, &spender);
+        require_not_frozen(&env, &from);
+
+        spend_allowance(&env, &from, &spender, amount);
+        spend_balance(&env, &from, amount);
+        decrease_supply(&env, amount);
 
-        storage::set_total_supply(&env, total_supply);
-        let mut balance_to = storage::get_balance(&env, &to);
-        balance_to += amount;
+        events::burn_event(&env, from, amount);
+    }
 
-        storage::set_balance(&env, &to, balance_to);
+    fn grant_minter(env: Env, minter: Address, allowance: i128) {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        require_nonnegative(&env, allowance);
 
-        events::transfer_event(&env, admin, to, amount);
+        storage::set_minter_allowance(&env, &minter, allowance);
+        events::minter_granted_event(&env, admin, minter, allowance);
     }
 
-    fn burn(env: Env, from: Address, amount: i128) {
-        from.require_auth();
-        let mut balance_from = storage::get_balance(&env, &from);
-        assert!(balance_from >= amount, "Insufficient balance");
+    fn revoke_minter(env: Env, minter: Address) {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
 
-        balance_from -= amount;
-        let mut total_supply = Self::total_supply(env.clone());
-        total_supply -= amount;
+        storage::remove_minter(&env, &minter);
+        events::minter_revoked_event(&env, admin, minter);
+    }
 
-        storage::set_balance(&env, &from, balance_from);
-        storage::set_total_supply(&env, total_supply);
+    fn minter_allowance(env: Env, minter: Address) -> Option<i128> {
+        storage::get_minter_allowance(&env, &minter)
+    }
 
-        events::transfer_event(&env, from, env.current_contract_address(), amount);
+    fn supply_cap(env: Env) -> Option<i128> {
+        storage::get_cap(&env)
     }
 
-    fn burn_from(env: Env, spender: Address, from: Address, amount: i128) {
-        spender.require_auth();
-        let mut allowance = storage::get_allowance(&env, &from, &spender);
-        assert!(allowance >= amount, "Allowance exceeded");
+    fn pause(env: Env) -> Result<(), TokenError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        if storage::is_paused(&env) {
+            return Err(TokenError::Paused);
+        }
+
+        storage::set_paused(&env, true);
+        events::paused_event(&env, admin);
+        Ok(())
+    }
+
+    fn unpause(env: Env) -> Result<(), TokenError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        if !storage::is_paused(&env) {
+            return Err(TokenError::NotPaused);
+        }
+
+        storage::set_paused(&env, false);
+        events::unpaused_event(&env, admin);
+        Ok(())
+    }
+
+    fn is_paused(env: Env) -> bool {
+        storage::is_paused(&env)
+    }
+
+    fn freeze(env: Env, account: Address) {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+
+        storage::set_frozen(&env, &account, true);
+        events::freeze_event(&env, admin, account);
+    }
+
+    fn unfreeze(env: Env, account: Address) {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+
+        storage::set_frozen(&env, &account, false);
+        events::unfreeze_event(&env, admin, account);
+    }
+
+    fn is_frozen(env: Env, account: Address) -> bool {
+        storage::is_frozen(&env, &account)
+    }
+
+    // Not subject to the pause, so funds can still be recovered during an
+    // incident
+    fn clawback(env: Env, from: Address, amount: i128) {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        require_nonnegative(&env, amount);
 
-        let mut balance_from = storage::get_balance(&env, &from);
-        assert!(balance_from >= amount, "Insufficient balance");
+        spend_balance(&env, &from, amount);
+        decrease_supply(&env, amount);
 
-        balance_from -= amount;
-        allowance -= amount;
-        let mut total_supply = Self::total_supply(env.clone());
-        total_supply -= amount;
+        events::clawback_event(&env, admin, from, amount);
+    }
+
+    fn balance_at(env: Env, id: Address, ledger: u32) -> Result<i128, TokenError> {
+        if ledger >= env.ledger().sequence() {
+            return Err(TokenError::FutureLedger);
+        }
+        Ok(checkpoints::at(&env, &CheckpointSeries::Balance(id), ledger))
+    }
+
+    fn total_supply_at(env: Env, ledger: u32) -> Result<i128, TokenError> {
+        if ledger >= env.ledger().sequence() {
+            return Err(TokenError::FutureLedger);
+        }
+        Ok(checkpoints::at(&env, &CheckpointSeries::TotalSupply, ledger))
+    }
+
+    fn delegate(env: Env, delegator: Address, delegatee: Address) {
+        delegator.require_auth();
+
+        let previous = storage::get_delegate(&env, &delegator);
+        storage::set_delegate(&env, &delegator, &delegatee);
+        events::delegate_changed_event(&env, delegator.clone(), previous.clone(), delegatee.clone());
+
+        let balance = storage::get_balance(&env, &delegator);
+        move_votes(&env, previous, Some(delegatee), balance);
+    }
 
-        storage::set_balance(&env, &from, balance_from);
-        storage::set_allowance(&env, &from, &spender, allowance);
-        storage::set_total_supply(&env, total_supply);
+    fn delegates(env: Env, delegator: Address) -> Option<Address> {
+        storage::get_delegate(&env, &delegator)
+    }
+
+    fn votes_of(env: Env, account: Address) -> i128 {
+        checkpoints::latest(&env, &CheckpointSeries::Votes(account))
+    }
+
+    fn past_votes(env: Env, account: Address, ledger: u32) -> Result<i128, TokenError> {
+        if ledger >= env.ledger().sequence() {
+            return Err(TokenError::FutureLedger);
+        }
+        Ok(checkpoints::at(&env, &CheckpointSeries::Votes(account), ledger))
+    }
+
+    fn set_fee(env: Env, fee_bps: u32, treasury: Address) -> Result<(), TokenError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        if fee_bps > MAX_FEE_BPS {
+            return Err(TokenError::InvalidFee);
+        }
+        if storage::is_frozen(&env, &treasury) {
+            return Err(TokenError::AccountFrozen);
+        }
+
+        storage::set_fee(&env, &FeeConfig { fee_bps, treasury: treasury.clone() });
+        events::fee_set_event(&env, admin, fee_bps, treasury);
+        Ok(())
+    }
+
+    fn fee(env: Env) -> Option<FeeConfig> {
+        storage::get_fee(&env)
+    }
+
+    fn set_fee_exempt(env: Env, account: Address, exempt: bool) {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+
+        storage::set_fee_exempt(&env, &account, exempt);
+        events::fee_exempt_event(&env, admin, account, exempt);
+    }
 
-        events::transfer_event(&env, from, env.current_contract_address(), amount);
+    fn is_fee_exempt(env: Env, account: Address) -> bool {
+        storage::is_fee_exempt(&env, &account)
     }
 }
@@ -5,19 +5,26 @@ mod token;
 mod storage;
 mod events;
 mod impls;
+mod error;
+mod checkpoints;
+pub mod permit;
+pub mod testutils;
 
 pub use crate::token::TokenTrait;
 pub use crate::impls::Token;
+pub use crate::error::TokenError;
+pub use crate::impls::MAX_FEE_BPS;
+pub use crate::storage::{FeeConfig, TokenMetadata};
 
-use soroban_sdk::{contractimpl, contract, String};
+use soroban_sdk::{contractimpl, contract, BytesN, String};
 
 #[contract]
 pub struct TokenContract;
 
 #[contractimpl]
 impl TokenTrait for TokenContract {
-    fn initialize(env: soroban_sdk::Env, admin: soroban_sdk::Address, total_supply: i128) {
-        Token::initialize(env, admin, total_supply);
+    fn __constructor(env: soroban_sdk::Env, admin: soroban_sdk::Address, total_supply: i128, cap: Option<i128>, metadata: TokenMetadata) -> Result<(), TokenError> {
+        Token::__constructor(env, admin, total_supply, cap, metadata)
     }
     fn name(env: soroban_sdk::Env) -> String {
         Token::name(env)
@@ -31,14 +38,29 @@ impl TokenTrait for TokenContract {
     fn total_supply(env: soroban_sdk::Env) -> i128 {
         Token::total_supply(env)
     }
+    fn balance(env: soroban_sdk::Env, id: soroban_sdk::Address) -> i128 {
+        Token::balance(env, id)
+    }
     fn balance_of(env: soroban_sdk::Env, owner: soroban_sdk::Address) -> i128 {
         Token::balance_of(env, owner)
     }
     fn allowance(env: soroban_sdk::Env, owner: soroban_sdk::Address, spender: soroban_sdk::Address) -> i128 {
         Token::allowance(env, owner, spender)
     }
-    fn approve(env: soroban_sdk::Env, from: soroban_sdk::Address, spender: soroban_sdk::Address, amount: i128) {
-        Token::approve(env, from, spender, amount)
+    fn approve(env: soroban_sdk::Env, from: soroban_sdk::Address, spender: soroban_sdk::Address, amount: i128, expiration_ledger: u32) {
+        Token::approve(env, from, spender, amount, expiration_ledger)
+    }
+    fn set_permit_key(env: soroban_sdk::Env, owner: soroban_sdk::Address, key: Option<BytesN<32>>) {
+        Token::set_permit_key(env, owner, key)
+    }
+    fn permit_key(env: soroban_sdk::Env, owner: soroban_sdk::Address) -> Option<BytesN<32>> {
+        Token::permit_key(env, owner)
+    }
+    fn permit(env: soroban_sdk::Env, owner: soroban_sdk::Address, spender: soroban_sdk::Address, amount: i128, expiration_ledger: u32, nonce: u64, signature: BytesN<64>) -> Result<(), TokenError> {
+        Token::permit(env, owner, spender, amount, expiration_ledger, nonce, signature)
+    }
+    fn permit_nonce(env: soroban_sdk::Env, owner: soroban_sdk::Address) -> u64 {
+        Token::permit_nonce(env, owner)
     }
     fn transfer(env: soroban_sdk::Env, from: soroban_sdk::Address, to: soroban_sdk::Address, amount: i128) {
         Token::transfer(env, from, to, amount)
@@ -46,8 +68,8 @@ impl TokenTrait for TokenContract {
     fn transfer_from(env: soroban_sdk::Env, spender: soroban_sdk::Address, from: soroban_sdk::Address, to: soroban_sdk::Address, amount: i128) {
         Token::transfer_from(env, spender, from, to, amount)
     }
-    fn mint(env: soroban_sdk::Env, to: soroban_sdk::Address, amount: i128) {
-        Token::mint(env, to, amount);
+    fn mint(env: soroban_sdk::Env, minter: soroban_sdk::Address, to: soroban_sdk::Address, amount: i128) {
+        Token::mint(env, minter, to, amount);
     }
     fn burn(env: soroban_sdk::Env, from: soroban_sdk::Address, amount: i128) {
         Token::burn(env, from, amount);
@@ -55,4 +77,67 @@ impl TokenTrait for TokenContract {
     fn burn_from(env: soroban_sdk::Env, spender: soroban_sdk::Address, from: soroban_sdk::Address, amount: i128) {
         Token::burn_from(env, spender, from, amount);
     }
+    fn grant_minter(env: soroban_sdk::Env, minter: soroban_sdk::Address, allowance: i128) {
+        Token::grant_minter(env, minter, allowance);
+    }
+    fn revoke_minter(env: soroban_sdk::Env, minter: soroban_sdk::Address) {
+        Token::revoke_minter(env, minter);
+    }
+    fn minter_allowance(env: soroban_sdk::Env, minter: soroban_sdk::Address) -> Option<i128> {
+        Token::minter_allowance(env, minter)
+    }
+    fn supply_cap(env: soroban_sdk::Env) -> Option<i128> {
+        Token::supply_cap(env)
+    }
+    fn pause(env: soroban_sdk::Env) -> Result<(), TokenError> {
+        Token::pause(env)
+    }
+    fn unpause(env: soroban_sdk::Env) -> Result<(), TokenError> {
+        Token::unpause(env)
+    }
+    fn is_paused(env: soroban_sdk::Env) -> bool {
+        Token::is_paused(env)
+    }
+    fn freeze(env: soroban_sdk::Env, account: soroban_sdk::Address) {
+        Token::freeze(env, account);
+    }
+    fn unfreeze(env: soroban_sdk::Env, account: soroban_sdk::Address) {
+        Token::unfreeze(env, account);
+    }
+    fn is_frozen(env: soroban_sdk::Env, account: soroban_sdk::Address) -> bool {
+        Token::is_frozen(env, account)
+    }
+    fn clawback(env: soroban_sdk::Env, from: soroban_sdk::Address, amount: i128) {
+        Token::clawback(env, from, amount);
+    }
+    fn balance_at(env: soroban_sdk::Env, id: soroban_sdk::Address, ledger: u32) -> Result<i128, TokenError> {
+        Token::balance_at(env, id, ledger)
+    }
+    fn total_supply_at(env: soroban_sdk::Env, ledger: u32) -> Result<i128, TokenError> {
+        Token::total_supply_at(env, ledger)
+    }
+    fn delegate(env: soroban_sdk::Env, delegator: soroban_sdk::Address, delegatee: soroban_sdk::Address) {
+        Token::delegate(env, delegator, delegatee);
+    }
+    fn delegates(env: soroban_sdk::Env, delegator: soroban_sdk::Address) -> Option<soroban_sdk::Address> {
+        Token::delegates(env, delegator)
+    }
+    fn votes_of(env: soroban_sdk::Env, account: soroban_sdk::Address) -> i128 {
+        Token::votes_of(env, account)
+    }
+    fn past_votes(env: soroban_sdk::Env, account: soroban_sdk::Address, ledger: u32) -> Result<i128, TokenError> {
+        Token::past_votes(env, account, ledger)
+    }
+    fn set_fee(env: soroban_sdk::Env, fee_bps: u32, treasury: soroban_sdk::Address) -> Result<(), TokenError> {
+        Token::set_fee(env, fee_bps, treasury)
+    }
+    fn fee(env: soroban_sdk::Env) -> Option<FeeConfig> {
+        Token::fee(env)
+    }
+    fn set_fee_exempt(env: soroban_sdk::Env, account: soroban_sdk::Address, exempt: bool) {
+        Token::set_fee_exempt(env, account, exempt);
+    }
+    fn is_fee_exempt(env: soroban_sdk::Env, account: soroban_sdk::Address) -> bool {
+        Token::is_fee_exempt(env, account)
+    }
 }
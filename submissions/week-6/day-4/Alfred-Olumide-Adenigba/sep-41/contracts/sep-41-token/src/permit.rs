@@ -0,0 +1,27 @@
+use soroban_sdk::{symbol_short, xdr::ToXdr, Address, Bytes, Env};
+
+/// What the owner's permit key signs. The network and token are part of
+/// it, so a permit cannot be replayed on another network or against another
+/// token, and so is the owner, so one key registered by two accounts cannot
+/// approve for both with one signature.
+pub fn payload(
+    env: &Env,
+    token: &Address,
+    owner: &Address,
+    spender: &Address,
+    amount: i128,
+    expiration_ledger: u32,
+    nonce: u64,
+) -> Bytes {
+    (
+        symbol_short!("permit"),
+        env.ledger().network_id(),
+        token.clone(),
+        owner.clone(),
+        spender.clone(),
+        amount,
+        expiration_ledger,
+        nonce,
+    )
+        .to_xdr(env)
+}
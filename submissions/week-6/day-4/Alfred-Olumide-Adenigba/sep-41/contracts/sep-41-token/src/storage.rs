@@ -1,33 +1,394 @@
-use soroban_sdk::{Address, Env, Symbol, symbol_short};
+use soroban_sdk::{contracttype, panic_with_error, Address, BytesN, Env, String};
 
-pub const TOTAL_SUPPLY: Symbol = symbol_short!("total");
+use crate::error::TokenError;
+
+// Ledgers close about every 5 seconds
+const DAY_IN_LEDGERS: u32 = 17280;
+
+// Admin, metadata, supply and cap live in instance storage and share the
+// contract's lifetime, so every call keeps them alive
+pub const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
+pub const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+// Balances and minter roles are per account and persistent; each touch
+// pushes them out another month
+pub const BALANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub const BALANCE_LIFETIME_THRESHOLD: u32 = BALANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    pub fee_bps: u32,
+    pub treasury: Address,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AllowanceDataKey {
+    pub from: Address,
+    pub spender: Address,
+}
+
+// Each series of checkpoints is stored one entry per checkpoint, so a
+// lookup only reads the entries its binary search visits
+#[contracttype]
+#[derive(Clone)]
+pub enum CheckpointSeries {
+    Balance(Address),
+    Votes(Address),
+    TotalSupply,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Checkpoint {
+    pub ledger: u32,
+    pub value: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    // Instance
+    Admin,
+    Metadata,
+    TotalSupply,
+    Cap,
+    Paused,
+    Fee,
+    // Persistent
+    Balance(Address),
+    Minter(Address),
+    Frozen(Address),
+    FeeExempt(Address),
+    Delegate(Address),
+    PermitNonce(Address),
+    PermitKey(Address),
+    CheckpointCount(CheckpointSeries),
+    Checkpoint(CheckpointSeries, u32),
+    // Temporary, lives until the allowance expires
+    Allowance(AllowanceDataKey),
+}
+
+pub fn extend_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+fn extend_persistent(env: &Env, key: &DataKey) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
 
 pub fn set_admin(env: &Env, admin: &Address) {
-    env.storage().persistent().set(&Symbol::new(env, "admin"), admin);
+    env.storage().instance().set(&DataKey::Admin, admin);
+    extend_instance(env);
+}
+
+pub fn get_admin(env: &Env) -> Address {
+    extend_instance(env);
+    env.storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .expect("Not initialized")
+}
+
+pub fn get_metadata(env: &Env) -> TokenMetadata {
+    extend_instance(env);
+    env.storage()
+        .instance()
+        .get(&DataKey::Metadata)
+        .expect("Not initialized")
+}
+
+pub fn set_metadata(env: &Env, metadata: &TokenMetadata) {
+    env.storage().instance().set(&DataKey::Metadata, metadata);
+    extend_instance(env);
+}
+
+pub fn get_cap(env: &Env) -> Option<i128> {
+    extend_instance(env);
+    env.storage().instance().get(&DataKey::Cap)
+}
+
+pub fn set_cap(env: &Env, cap: i128) {
+    env.storage().instance().set(&DataKey::Cap, &cap);
+    extend_instance(env);
+}
+
+pub fn is_paused(env: &Env) -> bool {
+    extend_instance(env);
+    env.storage()
+        .instance()
+        .get(&DataKey::Paused)
+        .unwrap_or(false)
+}
+
+pub fn set_paused(env: &Env, paused: bool) {
+    env.storage().instance().set(&DataKey::Paused, &paused);
+    extend_instance(env);
+}
+
+pub fn get_fee(env: &Env) -> Option<FeeConfig> {
+    extend_instance(env);
+    env.storage().instance().get(&DataKey::Fee)
+}
+
+pub fn set_fee(env: &Env, fee: &FeeConfig) {
+    env.storage().instance().set(&DataKey::Fee, fee);
+    extend_instance(env);
+}
+
+// Only exempt accounts have an entry
+pub fn is_fee_exempt(env: &Env, addr: &Address) -> bool {
+    let key = DataKey::FeeExempt(addr.clone());
+    let exempt = env.storage().persistent().has(&key);
+    if exempt {
+        extend_persistent(env, &key);
+    }
+    exempt
+}
+
+pub fn set_fee_exempt(env: &Env, addr: &Address, exempt: bool) {
+    let key = DataKey::FeeExempt(addr.clone());
+    if exempt {
+        env.storage().persistent().set(&key, &true);
+        extend_persistent(env, &key);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+// Only frozen accounts have an entry
+pub fn is_frozen(env: &Env, addr: &Address) -> bool {
+    let key = DataKey::Frozen(addr.clone());
+    let frozen = env.storage().persistent().has(&key);
+    if frozen {
+        extend_persistent(env, &key);
+    }
+    frozen
+}
+
+pub fn set_frozen(env: &Env, addr: &Address, frozen: bool) {
+    let key = DataKey::Frozen(addr.clone());
+    if frozen {
+        env.storage().persistent().set(&key, &true);
+        extend_persistent(env, &key);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+pub fn get_delegate(env: &Env, delegator: &Address) -> Option<Address> {
+    let key = DataKey::Delegate(delegator.clone());
+    let delegate = env.storage().persistent().get(&key);
+    if delegate.is_some() {
+        extend_persistent(env, &key);
+    }
+    delegate
+}
+
+pub fn set_delegate(env: &Env, delegator: &Address, delegate: &Address) {
+    let key = DataKey::Delegate(delegator.clone());
+    env.storage().persistent().set(&key, delegate);
+    extend_persistent(env, &key);
+}
+
+// The nonce the owner's next permit must carry
+pub fn get_permit_nonce(env: &Env, owner: &Address) -> u64 {
+    let key = DataKey::PermitNonce(owner.clone());
+    match env.storage().persistent().get(&key) {
+        Some(nonce) => {
+            extend_persistent(env, &key);
+            nonce
+        }
+        None => 0,
+    }
+}
+
+pub fn set_permit_nonce(env: &Env, owner: &Address, nonce: u64) {
+    let key = DataKey::PermitNonce(owner.clone());
+    env.storage().persistent().set(&key, &nonce);
+    extend_persistent(env, &key);
+}
+
+pub fn get_permit_key(env: &Env, owner: &Address) -> Option<BytesN<32>> {
+    let key = DataKey::PermitKey(owner.clone());
+    let permit_key = env.storage().persistent().get(&key);
+    if permit_key.is_some() {
+        extend_persistent(env, &key);
+    }
+    permit_key
+}
+
+pub fn set_permit_key(env: &Env, owner: &Address, permit_key: Option<BytesN<32>>) {
+    let key = DataKey::PermitKey(owner.clone());
+    match permit_key {
+        Some(permit_key) => {
+            env.storage().persistent().set(&key, &permit_key);
+            extend_persistent(env, &key);
+        }
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+pub fn get_checkpoint_count(env: &Env, series: &CheckpointSeries) -> u32 {
+    let key = DataKey::CheckpointCount(series.clone());
+    match env.storage().persistent().get(&key) {
+        Some(count) => {
+            extend_persistent(env, &key);
+            count
+        }
+        None => 0,
+    }
+}
+
+pub fn set_checkpoint_count(env: &Env, series: &CheckpointSeries, count: u32) {
+    let key = DataKey::CheckpointCount(series.clone());
+    env.storage().persistent().set(&key, &count);
+    extend_persistent(env, &key);
+}
+
+pub fn get_checkpoint(env: &Env, series: &CheckpointSeries, index: u32) -> Checkpoint {
+    let key = DataKey::Checkpoint(series.clone(), index);
+    let checkpoint = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .expect("Missing checkpoint");
+    extend_persistent(env, &key);
+    checkpoint
+}
+
+pub fn set_checkpoint(env: &Env, series: &CheckpointSeries, index: u32, checkpoint: &Checkpoint) {
+    let key = DataKey::Checkpoint(series.clone(), index);
+    env.storage().persistent().set(&key, checkpoint);
+    extend_persistent(env, &key);
+}
+
+// A minter's remaining allowance; no entry means no minter role
+pub fn get_minter_allowance(env: &Env, minter: &Address) -> Option<i128> {
+    let key = DataKey::Minter(minter.clone());
+    let allowance = env.storage().persistent().get(&key);
+    if allowance.is_some() {
+        extend_persistent(env, &key);
+    }
+    allowance
+}
+
+pub fn set_minter_allowance(env: &Env, minter: &Address, allowance: i128) {
+    let key = DataKey::Minter(minter.clone());
+    env.storage().persistent().set(&key, &allowance);
+    extend_persistent(env, &key);
+}
+
+pub fn remove_minter(env: &Env, minter: &Address) {
+    let key = DataKey::Minter(minter.clone());
+    env.storage().persistent().remove(&key);
 }
 
 pub fn get_balance(env: &Env, addr: &Address) -> i128 {
-    env.storage().persistent().get(addr).unwrap_or(0)
+    let key = DataKey::Balance(addr.clone());
+    match env.storage().persistent().get(&key) {
+        Some(balance) => {
+            extend_persistent(env, &key);
+            balance
+        }
+        None => 0,
+    }
 }
 
 pub fn set_balance(env: &Env, addr: &Address, amount: i128) {
-    env.storage().persistent().set(addr, &amount);
+    let key = DataKey::Balance(addr.clone());
+    env.storage().persistent().set(&key, &amount);
+    extend_persistent(env, &key);
 }
 
 pub fn get_total_supply(env: &Env) -> i128 {
-    env.storage().persistent().get(&TOTAL_SUPPLY).unwrap_or(0)
+    extend_instance(env);
+    env.storage()
+        .instance()
+        .get(&DataKey::TotalSupply)
+        .unwrap_or(0)
 }
 
 pub fn set_total_supply(env: &Env, amount: i128) {
-    env.storage().persistent().set(&TOTAL_SUPPLY, &amount);
+    env.storage().instance().set(&DataKey::TotalSupply, &amount);
+    extend_instance(env);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+fn allowance_key(owner: &Address, spender: &Address) -> DataKey {
+    DataKey::Allowance(AllowanceDataKey {
+        from: owner.clone(),
+        spender: spender.clone(),
+    })
+}
+
+// An allowance counts as zero once the ledger sequence is past its
+// expiration, even if the entry has not been evicted yet
+pub fn read_allowance(env: &Env, owner: &Address, spender: &Address) -> AllowanceValue {
+    let key = allowance_key(owner, spender);
+    match env.storage().temporary().get::<_, AllowanceValue>(&key) {
+        Some(allowance) if allowance.expiration_ledger >= env.ledger().sequence() => allowance,
+        Some(allowance) => AllowanceValue {
+            amount: 0,
+            expiration_ledger: allowance.expiration_ledger,
+        },
+        None => AllowanceValue {
+            amount: 0,
+            expiration_ledger: 0,
+        },
+    }
 }
 
 pub fn get_allowance(env: &Env, owner: &Address, spender: &Address) -> i128 {
-    let key = (owner.clone(), spender.clone());
-    env.storage().persistent().get(&key).unwrap_or(0)
+    read_allowance(env, owner, spender).amount
 }
 
-pub fn set_allowance(env: &Env, owner: &Address, spender: &Address, amount: i128) {
-    let key = (owner.clone(), spender.clone());
-    env.storage().persistent().set(&key, &amount);
+// Allowances live in temporary storage and are kept alive exactly until
+// their expiration ledger
+pub fn set_allowance(
+    env: &Env,
+    owner: &Address,
+    spender: &Address,
+    amount: i128,
+    expiration_ledger: u32,
+) {
+    let sequence = env.ledger().sequence();
+    if amount > 0 && expiration_ledger < sequence {
+        panic_with_error!(env, TokenError::ExpirationInPast);
+    }
+    // The entry's TTL cannot be extended past the network's maximum
+    if amount > 0 && expiration_ledger - sequence > env.storage().max_ttl() {
+        panic_with_error!(env, TokenError::ExpirationTooFar);
+    }
+
+    let key = allowance_key(owner, spender);
+    let allowance = AllowanceValue {
+        amount,
+        expiration_ledger,
+    };
+    env.storage().temporary().set(&key, &allowance);
+
+    if amount > 0 {
+        let live_for = expiration_ledger - sequence;
+        env.storage().temporary().extend_ttl(&key, live_for, live_for);
+    }
 }
@@ -1,22 +1,55 @@
 #![cfg(test)]
 
-use soroban_sdk::{testutils::Address as _, Address, Env, String, Symbol};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{
+        storage::{Instance as _, Persistent as _, Temporary as _},
+        Address as _, AuthorizedFunction, Events, Ledger,
+    },
+    Address, Env, IntoVal, String, Symbol, Val, Vec,
+};
 
-use crate::{TokenContract, TokenContractClient};
+use crate::storage::{
+    AllowanceDataKey, DataKey, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+};
+use crate::testutils::PermitSigner;
+use crate::{FeeConfig, TokenContract, TokenContractClient, TokenError, TokenMetadata, MAX_FEE_BPS};
+
+fn metadata(env: &Env, name: &str, symbol: &str, decimals: u32) -> TokenMetadata {
+    TokenMetadata {
+        name: String::from_str(env, name),
+        symbol: String::from_str(env, symbol),
+        decimals,
+    }
+}
+
+// What the client sees when an entry point panics with `e`
+fn error(e: TokenError) -> soroban_sdk::Error {
+    soroban_sdk::Error::from_contract_error(e as u32)
+}
+
+fn deploy(
+    env: &Env,
+    admin: &Address,
+    total_supply: i128,
+    cap: Option<i128>,
+    metadata: TokenMetadata,
+) -> TokenContractClient<'static> {
+    let contract_id = env.register(TokenContract, (admin.clone(), total_supply, cap, metadata));
+    TokenContractClient::new(env, &contract_id)
+}
 
 fn setup() -> (Env, TokenContractClient<'static>, Address, Address) {
     let env = Env::default();
     env.mock_all_auths();
 
-    // Register contract
-    let contract_id = env.register(TokenContract, ());
-    let client = TokenContractClient::new(&env, &contract_id);
-
     // Create test accounts
     let admin = Address::generate(&env);
     let user1 = Address::generate(&env);
 
-    client.initialize(&admin, &1000);
+    // Register contract
+    let client = deploy(&env, &admin, 1000, None, metadata(&env, "MyToken", "MTK", 18));
 
     (env, client, admin, user1)
 }
@@ -36,9 +69,94 @@ fn test_symbol() {
     assert_eq!(symbol, String::from_str(&env, "MTK"));
 }
 
+#[test]
+fn test_metadata_is_per_deployment() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+
+    let usd = deploy(&env, &admin, 1000, None, metadata(&env, "US Dollar", "USDC", 7));
+    let gold = deploy(&env, &admin, 1000, None, metadata(&env, "Gold", "GLD", 0));
+
+    assert_eq!(usd.name(), String::from_str(&env, "US Dollar"));
+    assert_eq!(usd.symbol(), String::from_str(&env, "USDC"));
+    assert_eq!(usd.decimals(), 7);
+    assert_eq!(gold.name(), String::from_str(&env, "Gold"));
+    assert_eq!(gold.symbol(), String::from_str(&env, "GLD"));
+    assert_eq!(gold.decimals(), 0);
+}
+
+#[test]
+fn test_constructor_runs_only_at_deploy() {
+    let (env, client, admin, user1) = setup();
+    let args: Vec<Val> = (user1.clone(), 1_i128, None::<i128>, metadata(&env, "Other", "OTH", 7)).into_val(&env);
+
+    // There is nothing left to call that sets the admin or the metadata
+    for name in ["initialize", "__constructor"] {
+        let result = env.try_invoke_contract::<(), soroban_sdk::Error>(
+            &client.address,
+            &Symbol::new(&env, name),
+            args.clone(),
+        );
+        assert!(result.is_err());
+    }
+    assert_eq!(client.name(), String::from_str(&env, "MyToken"));
+    assert_eq!(client.balance(&admin), 1000);
+    assert_eq!(client.balance_of(&user1), 0);
+}
+
+#[test]
+fn test_constructor_accepts_longest_symbol() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    let client = deploy(&env, &admin, 1000, None, metadata(&env, "MyToken", "ABCDEFGHIJKL", 18));
+    assert_eq!(client.decimals(), 18);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_constructor_rejects_too_many_decimals() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    deploy(&env, &admin, 1000, None, metadata(&env, "MyToken", "MTK", 19));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_constructor_rejects_empty_symbol() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    deploy(&env, &admin, 1000, None, metadata(&env, "MyToken", "", 18));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_constructor_rejects_long_symbol() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    deploy(&env, &admin, 1000, None, metadata(&env, "MyToken", "ABCDEFGHIJKLM", 18));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_constructor_rejects_cap_below_supply() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    deploy(&env, &admin, 1000, Some(999), metadata(&env, "MyToken", "MTK", 18));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_constructor_rejects_negative_supply() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    deploy(&env, &admin, -1, None, metadata(&env, "MyToken", "MTK", 18));
+}
+
 #[test]
 fn test_total_supply() {
-    let (env, client, _admin, _user1) = setup();
+    let (_env, client, _admin, _user1) = setup();
 
     let supply = client.total_supply();
     assert_eq!(supply, 1000);
@@ -46,7 +164,7 @@ fn test_total_supply() {
 
 #[test]
 fn test_transfer() {
-    let (env, client, admin, user1) = setup();
+    let (_env, client, admin, user1) = setup();
 
     // transfer from admin -> user1
     client.transfer(&admin, &user1, &100);
@@ -57,10 +175,10 @@ fn test_transfer() {
 
 #[test]
 fn test_approve_and_transfer_from() {
-    let (env, client, admin, user1) = setup();
+    let (_env, client, admin, user1) = setup();
 
     // admin approves user1 to spend 200 tokens
-    client.approve(&admin, &user1, &200);
+    client.approve(&admin, &user1, &200, &100);
 
     // check allowance
     let allowance = client.allowance(&admin, &user1);
@@ -79,7 +197,7 @@ fn test_approve_and_transfer_from() {
 
 #[test]
 fn test_burn() {
-    let (env, client, admin, user1) = setup();
+    let (_env, client, admin, user1) = setup();
 
     // Transfer some tokens to user1 first
     client.transfer(&admin, &user1, &200);
@@ -95,13 +213,13 @@ fn test_burn() {
 
 #[test]
 fn test_burn_from() {
-    let (env, client, admin, user1) = setup();
+    let (_env, client, admin, user1) = setup();
 
     // Transfer some tokens to user1
     client.transfer(&admin, &user1, &300);
     
     // User1 approves admin to burn 100 tokens on their behalf
-    client.approve(&user1, &admin, &100);
+    client.approve(&user1, &admin, &100, &100);
     
     // Check initial state
     assert_eq!(client.balance_of(&user1), 300);
@@ -115,3 +233,1016 @@ fn test_burn_from() {
     assert_eq!(client.total_supply(), 920);
     assert_eq!(client.allowance(&user1, &admin), 20);
 }
+
+#[test]
+fn test_admin_mints() {
+    let (env, client, admin, user1) = setup();
+
+    client.mint(&admin, &user1, &500);
+    assert_eq!(
+        env.auths()[0].1.function,
+        AuthorizedFunction::Contract((
+            client.address.clone(),
+            Symbol::new(&env, "mint"),
+            (admin.clone(), user1.clone(), 500_i128).into_val(&env),
+        ))
+    );
+
+    assert_eq!(client.balance_of(&user1), 500);
+    assert_eq!(client.total_supply(), 1500);
+}
+
+#[test]
+fn test_mint_needs_minter_role() {
+    let (env, client, admin, user1) = setup();
+    let minter = Address::generate(&env);
+
+    assert_eq!(client.try_mint(&user1, &user1, &1), Err(Ok(error(TokenError::NotMinter))));
+    assert_eq!(client.minter_allowance(&minter), None);
+
+    client.grant_minter(&minter, &300);
+    assert_eq!(client.minter_allowance(&minter), Some(300));
+
+    client.mint(&minter, &user1, &200);
+    assert_eq!(client.balance_of(&user1), 200);
+    assert_eq!(client.minter_allowance(&minter), Some(100));
+
+    // Only what is left of the allowance
+    assert_eq!(
+        client.try_mint(&minter, &user1, &101),
+        Err(Ok(error(TokenError::MinterAllowanceExceeded)))
+    );
+    client.mint(&minter, &user1, &100);
+    assert_eq!(client.minter_allowance(&minter), Some(0));
+
+    client.grant_minter(&minter, &50);
+    client.revoke_minter(&minter);
+    assert_eq!(client.try_mint(&minter, &user1, &1), Err(Ok(error(TokenError::NotMinter))));
+    assert_eq!(client.total_supply(), 1300);
+    assert_eq!(client.balance_of(&admin), 1000);
+}
+
+#[test]
+fn test_only_admin_grants_minters() {
+    let (env, client, admin, _user1) = setup();
+    let minter = Address::generate(&env);
+
+    client.grant_minter(&minter, &300);
+    let (signer, invocation) = &env.auths()[0];
+    assert_eq!(signer, &admin);
+    assert_eq!(
+        invocation.function,
+        AuthorizedFunction::Contract((
+            client.address.clone(),
+            Symbol::new(&env, "grant_minter"),
+            (minter.clone(), 300_i128).into_val(&env),
+        ))
+    );
+
+    env.set_auths(&[]);
+    assert!(client.try_grant_minter(&minter, &1).is_err());
+    assert!(client.try_revoke_minter(&minter).is_err());
+}
+
+#[test]
+fn test_supply_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+
+    let client = deploy(&env, &admin, 1000, Some(1500), metadata(&env, "MyToken", "MTK", 18));
+    assert_eq!(client.supply_cap(), Some(1500));
+
+    client.mint(&admin, &admin, &500);
+    assert_eq!(client.try_mint(&admin, &admin, &1), Err(Ok(error(TokenError::SupplyCapExceeded))));
+
+    // Burning frees room under the cap
+    client.burn(&admin, &10);
+    client.mint(&admin, &admin, &10);
+    assert_eq!(client.total_supply(), 1500);
+}
+
+#[test]
+fn test_mint_and_role_events() {
+    let (env, client, admin, user1) = setup();
+    let minter = Address::generate(&env);
+
+    client.grant_minter(&minter, &300);
+    assert_eq!(
+        env.events().all(),
+        soroban_sdk::vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("grant"), admin.clone(), minter.clone()).into_val(&env),
+                300_i128.into_val(&env)
+            )
+        ]
+    );
+
+    client.mint(&minter, &user1, &200);
+    assert_eq!(
+        env.events().all(),
+        soroban_sdk::vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("mint"), minter.clone(), user1.clone()).into_val(&env),
+                200_i128.into_val(&env)
+            )
+        ]
+    );
+
+    client.revoke_minter(&minter);
+    assert_eq!(
+        env.events().all(),
+        soroban_sdk::vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("revoke"), admin.clone(), minter.clone()).into_val(&env),
+                ().into_val(&env)
+            )
+        ]
+    );
+}
+
+#[test]
+fn test_allowance_expires() {
+    let (env, client, admin, user1) = setup();
+    env.ledger().set_sequence_number(100);
+
+    client.approve(&admin, &user1, &200, &110);
+    assert_eq!(
+        env.events().all(),
+        soroban_sdk::vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("approve"), admin.clone(), user1.clone()).into_val(&env),
+                (200_i128, 110_u32).into_val(&env)
+            )
+        ]
+    );
+
+    // Still usable on the expiration ledger itself
+    env.ledger().set_sequence_number(110);
+    client.transfer_from(&user1, &admin, &user1, &50);
+    assert_eq!(client.allowance(&admin, &user1), 150);
+
+    env.ledger().set_sequence_number(111);
+    assert_eq!(client.allowance(&admin, &user1), 0);
+    let exceeded = Err(Ok(error(TokenError::InsufficientAllowance)));
+    assert_eq!(client.try_transfer_from(&user1, &admin, &user1, &1), exceeded);
+    assert_eq!(client.try_burn_from(&user1, &admin, &1), exceeded);
+}
+
+#[test]
+fn test_approve_with_past_expiration() {
+    let (env, client, admin, user1) = setup();
+    env.ledger().set_sequence_number(100);
+
+    assert_eq!(client.try_approve(&admin, &user1, &1, &99), Err(Ok(error(TokenError::ExpirationInPast))));
+
+    // Clearing an allowance does not need a future ledger
+    client.approve(&admin, &user1, &200, &150);
+    client.approve(&admin, &user1, &0, &99);
+    assert_eq!(client.allowance(&admin, &user1), 0);
+}
+
+#[test]
+fn test_approve_past_max_ttl() {
+    let (env, client, admin, user1) = setup();
+    env.ledger().set_sequence_number(100);
+    let max_entry_ttl = env.ledger().get().max_entry_ttl;
+
+    let too_far = Err(Ok(error(TokenError::ExpirationTooFar)));
+    assert_eq!(client.try_approve(&admin, &user1, &1, &(100 + max_entry_ttl + 10)), too_far);
+    assert_eq!(client.try_approve(&admin, &user1, &1, &u32::MAX), too_far);
+    assert_eq!(client.allowance(&admin, &user1), 0);
+
+    // The furthest ledger the entry can live to is still fine
+    let furthest = 100 + env.as_contract(&client.address, || env.storage().max_ttl());
+    client.approve(&admin, &user1, &1, &furthest);
+    assert_eq!(client.allowance(&admin, &user1), 1);
+    client.approve(&admin, &user1, &0, &u32::MAX);
+    assert_eq!(client.allowance(&admin, &user1), 0);
+}
+
+#[test]
+fn test_allowance_ttl_follows_expiration() {
+    let (env, client, admin, user1) = setup();
+    env.ledger().set_sequence_number(100);
+
+    client.approve(&admin, &user1, &200, &1_100);
+    let ttl = env.as_contract(&client.address, || {
+        env.storage()
+            .temporary()
+            .get_ttl(&DataKey::Allowance(AllowanceDataKey {
+                from: admin.clone(),
+                spender: user1.clone(),
+            }))
+    });
+    assert_eq!(ttl, 1_000);
+
+    // Spending keeps the original expiration
+    client.transfer_from(&user1, &admin, &user1, &50);
+    env.ledger().set_sequence_number(1_100);
+    assert_eq!(client.allowance(&admin, &user1), 150);
+    env.ledger().set_sequence_number(1_101);
+    assert_eq!(client.allowance(&admin, &user1), 0);
+}
+
+#[test]
+fn test_sep41_conformance() {
+    let (env, client, admin, _user1) = setup();
+    client.mint(&admin, &admin, &sep41_conformance::MIN_BALANCE);
+
+    sep41_conformance::Sep41::new(&env, &client.address, &admin).run_all();
+}
+
+#[test]
+fn test_touched_entries_are_extended() {
+    let (env, client, admin, user1) = setup();
+
+    client.transfer(&admin, &user1, &100);
+    env.as_contract(&client.address, || {
+        assert_eq!(env.storage().instance().get_ttl(), INSTANCE_BUMP_AMOUNT);
+        assert_eq!(
+            env.storage()
+                .persistent()
+                .get_ttl(&DataKey::Balance(user1.clone())),
+            BALANCE_BUMP_AMOUNT
+        );
+    });
+
+    // Below the threshold a read is enough to push the entry out again
+    let sequence = env.ledger().sequence() + BALANCE_BUMP_AMOUNT - BALANCE_LIFETIME_THRESHOLD + 1;
+    env.ledger().set_sequence_number(sequence);
+    assert_eq!(client.balance(&user1), 100);
+    env.as_contract(&client.address, || {
+        assert_eq!(
+            env.storage()
+                .persistent()
+                .get_ttl(&DataKey::Balance(user1.clone())),
+            BALANCE_BUMP_AMOUNT
+        );
+    });
+}
+
+#[test]
+fn test_balances_survive_a_month_of_ledgers() {
+    let (env, client, admin, user1) = setup();
+    let minter = Address::generate(&env);
+
+    client.transfer(&admin, &user1, &100);
+    client.grant_minter(&minter, &50);
+
+    // The contract is called every few days, but the balance and minter
+    // entries are not touched again for almost a month
+    let end = env.ledger().sequence() + BALANCE_BUMP_AMOUNT - 1;
+    while env.ledger().sequence() < end {
+        let next = (env.ledger().sequence() + INSTANCE_LIFETIME_THRESHOLD).min(end);
+        env.ledger().set_sequence_number(next);
+        client.total_supply();
+    }
+
+    assert_eq!(client.balance(&user1), 100);
+    assert_eq!(client.total_supply(), 1000);
+    assert_eq!(client.minter_allowance(&minter), Some(50));
+    client.transfer(&user1, &admin, &100);
+    assert_eq!(client.balance(&admin), 1000);
+}
+
+#[test]
+fn test_pause_stops_token_movements() {
+    let (env, client, admin, user1) = setup();
+    client.approve(&admin, &user1, &100, &100);
+
+    client.pause();
+    assert_eq!(
+        env.events().all(),
+        soroban_sdk::vec![
+            &env,
+            (
+                client.address.clone(),
+                (Symbol::new(&env, "paused"), admin.clone()).into_val(&env),
+                ().into_val(&env)
+            )
+        ]
+    );
+    assert!(client.is_paused());
+
+    let paused = Err(Ok(error(TokenError::Paused)));
+    assert_eq!(client.try_transfer(&admin, &user1, &1), paused);
+    assert_eq!(client.try_transfer_from(&user1, &admin, &user1, &1), paused);
+    assert_eq!(client.try_burn(&admin, &1), paused);
+    assert_eq!(client.try_burn_from(&user1, &admin, &1), paused);
+    assert_eq!(client.try_mint(&admin, &user1, &1), paused);
+    assert_eq!(client.try_pause(), Err(Ok(TokenError::Paused)));
+
+    // Allowances can still be managed
+    client.approve(&admin, &user1, &50, &100);
+
+    client.unpause();
+    assert!(!client.is_paused());
+    assert_eq!(client.try_unpause(), Err(Ok(TokenError::NotPaused)));
+    client.transfer_from(&user1, &admin, &user1, &50);
+    assert_eq!(client.balance(&user1), 50);
+}
+
+#[test]
+fn test_only_admin_pauses() {
+    let (env, client, admin, _user1) = setup();
+
+    client.pause();
+    assert_eq!(env.auths()[0].0, admin);
+
+    env.set_auths(&[]);
+    assert!(client.try_unpause().is_err());
+    assert!(client.try_freeze(&admin).is_err());
+    assert!(client.try_clawback(&admin, &1).is_err());
+    assert!(client.is_paused());
+}
+
+#[test]
+fn test_frozen_account_cannot_send_or_receive() {
+    let (env, client, admin, user1) = setup();
+    let user2 = Address::generate(&env);
+    client.transfer(&admin, &user1, &300);
+    client.approve(&user1, &admin, &100, &100);
+    // user1 also acts for others, as a spender and a minter
+    client.transfer(&admin, &user2, &100);
+    client.approve(&user2, &user1, &100, &100);
+    client.grant_minter(&user1, &100);
+
+    client.freeze(&user1);
+    assert_eq!(
+        env.events().all(),
+        soroban_sdk::vec![
+            &env,
+            (
+                client.address.clone(),
+                (Symbol::new(&env, "freeze"), admin.clone(), user1.clone()).into_val(&env),
+                ().into_val(&env)
+            )
+        ]
+    );
+    assert!(client.is_frozen(&user1));
+    assert!(!client.is_frozen(&user2));
+
+    let frozen = Err(Ok(error(TokenError::AccountFrozen)));
+    assert_eq!(client.try_transfer(&user1, &user2, &1), frozen);
+    assert_eq!(client.try_transfer(&admin, &user1, &1), frozen);
+    assert_eq!(client.try_transfer_from(&admin, &user1, &user2, &1), frozen);
+    assert_eq!(client.try_burn(&user1, &1), frozen);
+    assert_eq!(client.try_burn_from(&admin, &user1, &1), frozen);
+    assert_eq!(client.try_mint(&admin, &user1, &1), frozen);
+    assert_eq!(client.try_transfer_from(&user1, &user2, &admin, &1), frozen);
+    assert_eq!(client.try_burn_from(&user1, &user2, &1), frozen);
+    assert_eq!(client.try_mint(&user1, &user2, &1), frozen);
+
+    // Everyone else is unaffected
+    client.transfer(&admin, &user2, &10);
+
+    client.unfreeze(&user1);
+    assert!(!client.is_frozen(&user1));
+    client.transfer(&user1, &user2, &10);
+    client.transfer_from(&user1, &user2, &admin, &10);
+    assert_eq!(client.balance(&user2), 110);
+}
+
+#[test]
+fn test_clawback() {
+    let (env, client, admin, user1) = setup();
+    client.transfer(&admin, &user1, &300);
+    client.freeze(&user1);
+    client.pause();
+
+    client.clawback(&user1, &200);
+    assert_eq!(
+        env.events().all(),
+        soroban_sdk::vec![
+            &env,
+            (
+                client.address.clone(),
+                (Symbol::new(&env, "clawback"), admin.clone(), user1.clone()).into_val(&env),
+                200_i128.into_val(&env)
+            )
+        ]
+    );
+    assert_eq!(client.balance(&user1), 100);
+    assert_eq!(client.total_supply(), 800);
+    assert_eq!(client.try_clawback(&user1, &101), Err(Ok(error(TokenError::InsufficientBalance))));
+}
+
+#[test]
+fn test_negative_amounts_are_rejected() {
+    let (_env, client, admin, user1) = setup();
+    client.transfer(&admin, &user1, &100);
+    client.approve(&user1, &admin, &100, &100);
+    client.grant_minter(&user1, &100);
+
+    let negative = Err(Ok(error(TokenError::NegativeAmount)));
+    assert_eq!(client.try_transfer(&user1, &admin, &-50), negative);
+    assert_eq!(client.try_transfer_from(&admin, &user1, &admin, &-50), negative);
+    assert_eq!(client.try_approve(&user1, &admin, &-50, &100), negative);
+    assert_eq!(client.try_mint(&admin, &user1, &-50), negative);
+    assert_eq!(client.try_mint(&user1, &user1, &-50), negative);
+    assert_eq!(client.try_burn(&user1, &-50), negative);
+    assert_eq!(client.try_burn_from(&admin, &user1, &-50), negative);
+    assert_eq!(client.try_clawback(&user1, &-50), negative);
+    assert_eq!(client.try_grant_minter(&user1, &-50), negative);
+
+    assert_eq!(client.balance(&admin), 900);
+    assert_eq!(client.balance(&user1), 100);
+    assert_eq!(client.allowance(&user1, &admin), 100);
+    assert_eq!(client.minter_allowance(&user1), Some(100));
+    assert_eq!(client.total_supply(), 1000);
+}
+
+#[test]
+fn test_mint_overflow() {
+    let (_env, client, admin, user1) = setup();
+
+    assert_eq!(
+        client.try_mint(&admin, &user1, &i128::MAX),
+        Err(Ok(error(TokenError::Overflow)))
+    );
+    client.mint(&admin, &user1, &(i128::MAX - 1000));
+    assert_eq!(client.total_supply(), i128::MAX);
+    assert_eq!(
+        client.try_mint(&admin, &admin, &1),
+        Err(Ok(error(TokenError::Overflow)))
+    );
+    assert_eq!(client.try_transfer(&user1, &admin, &(i128::MAX - 1000)), Ok(Ok(())));
+    assert_eq!(client.balance(&admin), i128::MAX);
+}
+
+#[test]
+fn test_insufficient_balance() {
+    let (_env, client, admin, user1) = setup();
+    client.approve(&admin, &user1, &5000, &100);
+
+    let insufficient = Err(Ok(error(TokenError::InsufficientBalance)));
+    assert_eq!(client.try_transfer(&user1, &admin, &1), insufficient);
+    assert_eq!(client.try_transfer_from(&user1, &admin, &user1, &1001), insufficient);
+    assert_eq!(client.try_burn(&admin, &1001), insufficient);
+    assert_eq!(client.try_burn_from(&user1, &admin, &1001), insufficient);
+}
+
+#[test]
+fn test_balance_checkpoints() {
+    let (env, client, admin, user1) = setup();
+    let start = env.ledger().sequence();
+
+    env.ledger().set_sequence_number(start + 10);
+    client.transfer(&admin, &user1, &100);
+    // Only the last write in a ledger is kept
+    client.transfer(&admin, &user1, &100);
+
+    env.ledger().set_sequence_number(start + 20);
+    client.mint(&admin, &user1, &500);
+    client.burn(&admin, &50);
+
+    env.ledger().set_sequence_number(start + 30);
+    assert_eq!(client.balance_at(&user1, &start), 0);
+    assert_eq!(client.balance_at(&user1, &(start + 9)), 0);
+    assert_eq!(client.balance_at(&user1, &(start + 10)), 200);
+    assert_eq!(client.balance_at(&user1, &(start + 19)), 200);
+    assert_eq!(client.balance_at(&user1, &(start + 20)), 700);
+    assert_eq!(client.balance_at(&admin, &start), 1000);
+    assert_eq!(client.balance_at(&admin, &(start + 10)), 800);
+    assert_eq!(client.balance_at(&admin, &(start + 29)), 750);
+
+    assert_eq!(client.total_supply_at(&start), 1000);
+    assert_eq!(client.total_supply_at(&(start + 19)), 1000);
+    assert_eq!(client.total_supply_at(&(start + 20)), 1450);
+
+    env.as_contract(&client.address, || {
+        let series = crate::storage::CheckpointSeries::Balance(user1.clone());
+        assert_eq!(crate::storage::get_checkpoint_count(&env, &series), 2);
+    });
+}
+
+#[test]
+fn test_checkpoints_only_answer_for_past_ledgers() {
+    let (env, client, admin, _user1) = setup();
+    let now = env.ledger().sequence();
+
+    assert_eq!(client.try_balance_at(&admin, &now), Err(Ok(TokenError::FutureLedger)));
+    assert_eq!(client.try_total_supply_at(&(now + 1)), Err(Ok(TokenError::FutureLedger)));
+    assert_eq!(client.try_past_votes(&admin, &now), Err(Ok(TokenError::FutureLedger)));
+
+    env.ledger().set_sequence_number(now + 1);
+    assert_eq!(client.balance_at(&admin, &now), 1000);
+}
+
+#[test]
+fn test_recent_checkpoints_outlive_old_ones() {
+    let (env, client, admin, user1) = setup();
+    let day = 17_280;
+    client.delegate(&admin, &admin);
+
+    // Early history nobody looks at again, then 40 days of a transfer
+    // every 5, so the TTL of everything older than a month runs out
+    let mut ledger = env.ledger().sequence();
+    for _ in 0..7 {
+        ledger += 1;
+        env.ledger().set_sequence_number(ledger);
+        client.transfer(&admin, &user1, &1);
+    }
+    for _ in 0..8 {
+        ledger += 5 * day;
+        env.ledger().set_sequence_number(ledger);
+        client.transfer(&admin, &user1, &1);
+    }
+
+    env.ledger().set_sequence_number(ledger + 1);
+    assert_eq!(client.past_votes(&admin, &ledger), 985);
+    assert_eq!(client.balance_at(&admin, &ledger), 985);
+    assert_eq!(client.votes_of(&admin), 985);
+}
+
+#[test]
+fn test_delegation() {
+    let (env, client, admin, user1) = setup();
+    let user2 = Address::generate(&env);
+    let start = env.ledger().sequence();
+
+    // Balances carry no votes until delegated
+    assert_eq!(client.votes_of(&admin), 0);
+    assert_eq!(client.delegates(&admin), None);
+
+    client.delegate(&admin, &admin);
+    assert_eq!(
+        env.events().all(),
+        soroban_sdk::vec![
+            &env,
+            (
+                client.address.clone(),
+                (Symbol::new(&env, "delegate"), admin.clone()).into_val(&env),
+                (None::<Address>, admin.clone()).into_val(&env)
+            ),
+            (
+                client.address.clone(),
+                (Symbol::new(&env, "votes"), admin.clone()).into_val(&env),
+                (0_i128, 1000_i128).into_val(&env)
+            )
+        ]
+    );
+    assert_eq!(client.delegates(&admin), Some(admin.clone()));
+    assert_eq!(client.votes_of(&admin), 1000);
+
+    // Votes follow the tokens to the receiver's delegate
+    env.ledger().set_sequence_number(start + 10);
+    client.delegate(&user1, &user2);
+    client.transfer(&admin, &user1, &300);
+    assert_eq!(client.votes_of(&admin), 700);
+    assert_eq!(client.votes_of(&user2), 300);
+    assert_eq!(client.votes_of(&user1), 0);
+
+    // Moving the delegation moves the whole balance
+    env.ledger().set_sequence_number(start + 20);
+    client.delegate(&user1, &admin);
+    assert_eq!(client.votes_of(&admin), 1000);
+    assert_eq!(client.votes_of(&user2), 0);
+
+    env.ledger().set_sequence_number(start + 30);
+    client.burn(&user1, &100);
+    assert_eq!(client.votes_of(&admin), 900);
+
+    env.ledger().set_sequence_number(start + 40);
+    assert_eq!(client.past_votes(&admin, &start), 1000);
+    assert_eq!(client.past_votes(&admin, &(start + 10)), 700);
+    assert_eq!(client.past_votes(&user2, &(start + 10)), 300);
+    assert_eq!(client.past_votes(&user2, &(start + 9)), 0);
+    assert_eq!(client.past_votes(&admin, &(start + 20)), 1000);
+    assert_eq!(client.past_votes(&user2, &(start + 20)), 0);
+    assert_eq!(client.past_votes(&admin, &(start + 30)), 900);
+}
+
+#[test]
+fn test_only_delegator_delegates() {
+    let (env, client, admin, user1) = setup();
+
+    client.delegate(&admin, &user1);
+    assert_eq!(
+        env.auths()[0].1.function,
+        AuthorizedFunction::Contract((
+            client.address.clone(),
+            Symbol::new(&env, "delegate"),
+            (admin.clone(), user1.clone()).into_val(&env),
+        ))
+    );
+
+    env.set_auths(&[]);
+    assert!(client.try_delegate(&admin, &admin).is_err());
+    assert_eq!(client.delegates(&admin), Some(user1));
+}
+
+#[test]
+fn test_transfer_fee() {
+    let (env, client, admin, user1) = setup();
+    let user2 = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    client.transfer(&admin, &user1, &1000);
+
+    client.set_fee(&250, &treasury);
+    assert_eq!(
+        env.events().all(),
+        soroban_sdk::vec![
+            &env,
+            (
+                client.address.clone(),
+                (Symbol::new(&env, "fee_set"), admin.clone()).into_val(&env),
+                (250_u32, treasury.clone()).into_val(&env)
+            )
+        ]
+    );
+    assert_eq!(client.fee(), Some(FeeConfig { fee_bps: 250, treasury: treasury.clone() }));
+
+    // 2.5% of 400 comes out of what the receiver gets
+    client.transfer(&user1, &user2, &400);
+    assert_eq!(
+        env.events().all(),
+        soroban_sdk::vec![
+            &env,
+            (
+                client.address.clone(),
+                (Symbol::new(&env, "fee"), user1.clone(), treasury.clone()).into_val(&env),
+                10_i128.into_val(&env)
+            ),
+            (
+                client.address.clone(),
+                (symbol_short!("transfer"), user1.clone(), user2.clone()).into_val(&env),
+                390_i128.into_val(&env)
+            )
+        ]
+    );
+    assert_eq!(client.balance(&user1), 600);
+    assert_eq!(client.balance(&user2), 390);
+    assert_eq!(client.balance(&treasury), 10);
+
+    client.approve(&user1, &user2, &200, &100);
+    client.transfer_from(&user2, &user1, &user2, &200);
+    assert_eq!(client.balance(&user1), 400);
+    assert_eq!(client.balance(&user2), 585);
+    assert_eq!(client.balance(&treasury), 15);
+    assert_eq!(client.allowance(&user1, &user2), 0);
+
+    // Rounds down, so dust transfers pay nothing
+    client.transfer(&user1, &user2, &39);
+    assert_eq!(client.balance(&treasury), 15);
+
+    // Minting and burning are not transfers
+    client.mint(&admin, &user1, &1000);
+    client.burn(&user1, &100);
+    assert_eq!(client.balance(&treasury), 15);
+    assert_eq!(client.total_supply(), 1900);
+
+    client.set_fee(&0, &treasury);
+    client.transfer(&user1, &user2, &400);
+    assert_eq!(client.balance(&treasury), 15);
+}
+
+#[test]
+fn test_fee_exemptions() {
+    let (env, client, admin, user1) = setup();
+    let payroll = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    client.set_fee(&1000, &treasury);
+
+    client.set_fee_exempt(&payroll, &true);
+    assert_eq!(
+        env.events().all(),
+        soroban_sdk::vec![
+            &env,
+            (
+                client.address.clone(),
+                (Symbol::new(&env, "exempt"), admin.clone(), payroll.clone()).into_val(&env),
+                true.into_val(&env)
+            )
+        ]
+    );
+    assert!(client.is_fee_exempt(&payroll));
+    assert!(!client.is_fee_exempt(&user1));
+
+    // Either side being exempt is enough
+    client.transfer(&admin, &payroll, &500);
+    client.transfer(&payroll, &user1, &200);
+    assert_eq!(client.balance(&payroll), 300);
+    assert_eq!(client.balance(&user1), 200);
+    assert_eq!(client.balance(&treasury), 0);
+
+    // So is an exempt spender pulling between two accounts that are not
+    let user2 = Address::generate(&env);
+    client.approve(&user1, &payroll, &100, &100);
+    client.transfer_from(&payroll, &user1, &user2, &100);
+    assert_eq!(client.balance(&user2), 100);
+    assert_eq!(client.balance(&treasury), 0);
+
+    client.set_fee_exempt(&payroll, &false);
+    client.transfer(&payroll, &user1, &100);
+    assert_eq!(client.balance(&user1), 190);
+    assert_eq!(client.balance(&treasury), 10);
+    client.approve(&user1, &payroll, &100, &100);
+    client.transfer_from(&payroll, &user1, &user2, &100);
+    assert_eq!(client.balance(&user2), 190);
+    assert_eq!(client.balance(&treasury), 20);
+}
+
+#[test]
+fn test_fee_treasury_cannot_be_frozen() {
+    let (env, client, admin, user1) = setup();
+    let user2 = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    client.transfer(&admin, &user1, &500);
+
+    client.freeze(&treasury);
+    assert_eq!(client.try_set_fee(&100, &treasury), Err(Ok(TokenError::AccountFrozen)));
+    assert_eq!(client.fee(), None);
+
+    // Frozen after the fee was set, transfers that owe a fee stop
+    client.unfreeze(&treasury);
+    client.set_fee(&100, &treasury);
+    client.freeze(&treasury);
+    assert_eq!(
+        client.try_transfer(&user1, &user2, &200),
+        Err(Ok(error(TokenError::AccountFrozen)))
+    );
+    assert_eq!(client.balance(&treasury), 0);
+    assert_eq!(client.balance(&user1), 500);
+
+    // Ones that owe nothing still go through
+    client.transfer(&user1, &user2, &99);
+    assert_eq!(client.balance(&user2), 99);
+}
+
+#[test]
+fn test_fee_is_capped_and_admin_only() {
+    let (env, client, admin, user1) = setup();
+
+    assert_eq!(
+        client.try_set_fee(&(MAX_FEE_BPS + 1), &user1),
+        Err(Ok(TokenError::InvalidFee))
+    );
+    assert_eq!(client.fee(), None);
+
+    client.set_fee(&MAX_FEE_BPS, &user1);
+    assert_eq!(
+        env.auths()[0].1.function,
+        AuthorizedFunction::Contract((
+            client.address.clone(),
+            Symbol::new(&env, "set_fee"),
+            (MAX_FEE_BPS, user1.clone()).into_val(&env),
+        ))
+    );
+    assert_eq!(env.auths()[0].0, admin);
+
+    env.set_auths(&[]);
+    assert!(client.try_set_fee(&0, &user1).is_err());
+    assert!(client.try_set_fee_exempt(&user1, &true).is_err());
+    assert_eq!(client.fee().map(|fee| fee.fee_bps), Some(MAX_FEE_BPS));
+}
+
+// An owner that has registered the permit key of `seed`
+fn permit_owner(env: &Env, client: &TokenContractClient, seed: [u8; 32]) -> (Address, PermitSigner) {
+    let owner = Address::generate(env);
+    let signer = PermitSigner::from_seed(&owner, seed);
+    client.set_permit_key(&owner, &Some(signer.public_key(env)));
+    (owner, signer)
+}
+
+#[test]
+fn test_permit_key_needs_the_owner() {
+    let (env, client, _admin, user1) = setup();
+    let owner = Address::generate(&env);
+    let signer = PermitSigner::from_seed(&owner, [1; 32]);
+    let key = Some(signer.public_key(&env));
+
+    client.set_permit_key(&owner, &key);
+    assert_eq!(
+        env.auths()[0].1.function,
+        AuthorizedFunction::Contract((
+            client.address.clone(),
+            Symbol::new(&env, "set_permit_key"),
+            (owner.clone(), key.clone()).into_val(&env),
+        ))
+    );
+    assert_eq!(env.auths()[0].0, owner);
+    assert_eq!(client.permit_key(&owner), key);
+
+    env.set_auths(&[]);
+    assert!(client.try_set_permit_key(&owner, &None).is_err());
+    assert_eq!(client.permit_key(&owner), key);
+
+    // Without a registered key no signature is accepted, not even one made
+    // with the key of the owner's account
+    env.mock_all_auths();
+    client.set_permit_key(&owner, &None);
+    assert_eq!(client.permit_key(&owner), None);
+    let signature = signer.sign(&env, &client.address, &user1, 300, 100, 0);
+    assert_eq!(
+        client.try_permit(&owner, &user1, &300, &100, &0, &signature),
+        Err(Ok(TokenError::NoPermitKey))
+    );
+}
+
+#[test]
+fn test_permit() {
+    let (env, client, admin, user1) = setup();
+    let (owner, signer) = permit_owner(&env, &client, [1; 32]);
+    client.transfer(&admin, &owner, &500);
+
+    let signature = signer.sign(&env, &client.address, &user1, 300, 100, 0);
+
+    // Anyone can submit it, the signature is the owner's authorization
+    env.set_auths(&[]);
+    client.permit(&owner, &user1, &300, &100, &0, &signature);
+    assert!(env.auths().is_empty());
+    assert_eq!(
+        env.events().all(),
+        soroban_sdk::vec![
+            &env,
+            (
+                client.address.clone(),
+                (symbol_short!("approve"), owner.clone(), user1.clone()).into_val(&env),
+                (300_i128, 100_u32).into_val(&env)
+            )
+        ]
+    );
+    assert_eq!(client.allowance(&owner, &user1), 300);
+    assert_eq!(client.permit_nonce(&owner), 1);
+
+    env.mock_all_auths();
+    client.transfer_from(&user1, &owner, &user1, &300);
+    assert_eq!(client.balance(&owner), 200);
+    assert_eq!(client.balance(&user1), 300);
+}
+
+#[test]
+fn test_permit_nonces_prevent_replay() {
+    let (env, client, _admin, user1) = setup();
+    let (owner, signer) = permit_owner(&env, &client, [1; 32]);
+
+    let first = signer.sign(&env, &client.address, &user1, 300, 100, 0);
+    client.permit(&owner, &user1, &300, &100, &0, &first);
+    assert_eq!(
+        client.try_permit(&owner, &user1, &300, &100, &0, &first),
+        Err(Ok(TokenError::InvalidNonce))
+    );
+
+    // Nonces are used strictly in order
+    let skipped = signer.sign(&env, &client.address, &user1, 50, 100, 2);
+    assert_eq!(
+        client.try_permit(&owner, &user1, &50, &100, &2, &skipped),
+        Err(Ok(TokenError::InvalidNonce))
+    );
+
+    let second = signer.sign(&env, &client.address, &user1, 0, 100, 1);
+    client.permit(&owner, &user1, &0, &100, &1, &second);
+    assert_eq!(client.allowance(&owner, &user1), 0);
+    assert_eq!(client.permit_nonce(&owner), 2);
+}
+
+#[test]
+fn test_permit_rejects_bad_signatures() {
+    let (env, client, _admin, user1) = setup();
+    let (owner, signer) = permit_owner(&env, &client, [1; 32]);
+    let other = PermitSigner::from_seed(&owner, [2; 32]);
+    let user2 = Address::generate(&env);
+
+    let signature = signer.sign(&env, &client.address, &user1, 300, 100, 0);
+    // Any field that differs from what was signed
+    assert!(client.try_permit(&owner, &user1, &301, &100, &0, &signature).is_err());
+    assert!(client.try_permit(&owner, &user1, &300, &101, &0, &signature).is_err());
+    assert!(client.try_permit(&owner, &user2, &300, &100, &0, &signature).is_err());
+
+    // Signed by a key the owner has not registered, or for another token
+    let forged = other.sign(&env, &client.address, &user1, 300, 100, 0);
+    assert!(client.try_permit(&owner, &user1, &300, &100, &0, &forged).is_err());
+    let other_token = deploy(&env, &owner, 1000, None, metadata(&env, "Other", "OTH", 7)).address;
+    let elsewhere = signer.sign(&env, &other_token, &user1, 300, 100, 0);
+    assert!(client.try_permit(&owner, &user1, &300, &100, &0, &elsewhere).is_err());
+
+    // Another owner registering the same key cannot reuse the signature
+    let (twin, _) = permit_owner(&env, &client, [1; 32]);
+    assert!(client.try_permit(&twin, &user1, &300, &100, &0, &signature).is_err());
+
+    assert_eq!(client.allowance(&owner, &user1), 0);
+    assert_eq!(client.permit_nonce(&owner), 0);
+    client.permit(&owner, &user1, &300, &100, &0, &signature);
+}
+
+#[test]
+fn test_permit_validates_like_approve() {
+    let (env, client, _admin, user1) = setup();
+    let (owner, signer) = permit_owner(&env, &client, [1; 32]);
+    env.ledger().set_sequence_number(100);
+
+    let expired = signer.sign(&env, &client.address, &user1, 300, 99, 0);
+    assert_eq!(
+        client.try_permit(&owner, &user1, &300, &99, &0, &expired),
+        Err(Ok(TokenError::ExpirationInPast))
+    );
+    let negative = signer.sign(&env, &client.address, &user1, -1, 200, 0);
+    assert_eq!(
+        client.try_permit(&owner, &user1, &-1, &200, &0, &negative),
+        Err(Ok(TokenError::NegativeAmount))
+    );
+    assert_eq!(client.permit_nonce(&owner), 0);
+}
+
+mod properties {
+    extern crate std;
+
+    use proptest::prelude::*;
+    use soroban_sdk::{testutils::Address as _, Address, Env};
+    use std::vec::Vec;
+
+    use super::{deploy, metadata};
+
+    const ACCOUNTS: usize = 4;
+
+    #[derive(Clone, Debug)]
+    enum Op {
+        Transfer(usize, usize, i128),
+        Approve(usize, usize, i128),
+        TransferFrom(usize, usize, usize, i128),
+        Mint(usize, i128),
+        Burn(usize, i128),
+        BurnFrom(usize, usize, i128),
+        Clawback(usize, i128),
+    }
+
+    // Mostly small amounts, with negatives and values at the edge of i128
+    // mixed in
+    fn amount() -> impl Strategy<Value = i128> {
+        prop_oneof![
+            8 => -100_i128..2_000,
+            1 => Just(i128::MAX),
+            1 => Just(i128::MIN),
+        ]
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        let account = 0..ACCOUNTS;
+        prop_oneof![
+            (account.clone(), account.clone(), amount()).prop_map(|(f, t, a)| Op::Transfer(f, t, a)),
+            (account.clone(), account.clone(), amount()).prop_map(|(f, s, a)| Op::Approve(f, s, a)),
+            (account.clone(), account.clone(), account.clone(), amount())
+                .prop_map(|(s, f, t, a)| Op::TransferFrom(s, f, t, a)),
+            (account.clone(), amount()).prop_map(|(t, a)| Op::Mint(t, a)),
+            (account.clone(), amount()).prop_map(|(f, a)| Op::Burn(f, a)),
+            (account.clone(), account.clone(), amount()).prop_map(|(s, f, a)| Op::BurnFrom(s, f, a)),
+            (account, amount()).prop_map(|(f, a)| Op::Clawback(f, a)),
+        ]
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn total_supply_matches_balances(ops in proptest::collection::vec(op(), 1..24)) {
+            let env = Env::default();
+            env.mock_all_auths();
+            let accounts: Vec<Address> = (0..ACCOUNTS).map(|_| Address::generate(&env)).collect();
+            // accounts[0] is the admin
+            let client = deploy(&env, &accounts[0], 1000, None, metadata(&env, "MyToken", "MTK", 18));
+            // Fees only move tokens between accounts, so the last account
+            // doubles as the treasury
+            client.set_fee(&250, &accounts[ACCOUNTS - 1]);
+            // With everyone delegating, votes add up to the supply too
+            for (i, id) in accounts.iter().enumerate() {
+                client.delegate(id, &accounts[(i + 1) % ACCOUNTS]);
+            }
+
+            for op in ops {
+                // Failed calls are rolled back, so only the invariant matters
+                let _ = match op {
+                    Op::Transfer(f, t, a) => client.try_transfer(&accounts[f], &accounts[t], &a),
+                    Op::Approve(f, s, a) => client.try_approve(&accounts[f], &accounts[s], &a, &100),
+                    Op::TransferFrom(s, f, t, a) => {
+                        client.try_transfer_from(&accounts[s], &accounts[f], &accounts[t], &a)
+                    }
+                    Op::Mint(t, a) => client.try_mint(&accounts[0], &accounts[t], &a),
+                    Op::Burn(f, a) => client.try_burn(&accounts[f], &a),
+                    Op::BurnFrom(s, f, a) => client.try_burn_from(&accounts[s], &accounts[f], &a),
+                    Op::Clawback(f, a) => client.try_clawback(&accounts[f], &a),
+                };
+
+                let balances: Vec<i128> = accounts.iter().map(|id| client.balance(id)).collect();
+                prop_assert!(balances.iter().all(|balance| *balance >= 0));
+                prop_assert_eq!(balances.iter().sum::<i128>(), client.total_supply());
+                let votes: Vec<i128> = accounts.iter().map(|id| client.votes_of(id)).collect();
+                prop_assert_eq!(votes.iter().sum::<i128>(), client.total_supply());
+                for from in &accounts {
+                    for spender in &accounts {
+                        prop_assert!(client.allowance(from, spender) >= 0);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+#![cfg(any(test, feature = "testutils"))]
+
+extern crate std;
+
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{Address, BytesN, Env};
+use std::vec::Vec;
+
+use crate::permit;
+
+/// Holds an owner's permit key and signs that owner's permits with it the
+/// way a wallet would, outside the contract. The owner registers
+/// `public_key` with `set_permit_key` first.
+pub struct PermitSigner {
+    owner: Address,
+    key: SigningKey,
+}
+
+impl PermitSigner {
+    pub fn from_seed(owner: &Address, seed: [u8; 32]) -> Self {
+        PermitSigner {
+            owner: owner.clone(),
+            key: SigningKey::from_bytes(&seed),
+        }
+    }
+
+    pub fn public_key(&self, env: &Env) -> BytesN<32> {
+        BytesN::from_array(env, &self.key.verifying_key().to_bytes())
+    }
+
+    pub fn sign(
+        &self,
+        env: &Env,
+        token: &Address,
+        spender: &Address,
+        amount: i128,
+        expiration_ledger: u32,
+        nonce: u64,
+    ) -> BytesN<64> {
+        let payload: Vec<u8> = permit::payload(
+            env,
+            token,
+            &self.owner,
+            spender,
+            amount,
+            expiration_ledger,
+            nonce,
+        )
+        .iter()
+        .collect();
+        BytesN::from_array(env, &self.key.sign(&payload).to_bytes())
+    }
+}
@@ -1,21 +1,98 @@
-use soroban_sdk::{Address, Env, String};
+use soroban_sdk::{Address, BytesN, Env, String};
+
+use crate::error::TokenError;
+use crate::storage::{FeeConfig, TokenMetadata};
 
 /// Trait for SEP-41 Token Standard
 pub trait TokenTrait {
-    fn initialize(env: Env, admin: Address, total_supply: i128);
+    /// Runs once, when the contract is deployed, so nobody can claim the
+    /// admin afterwards. `metadata` is fixed from then on, so several tokens
+    /// can be deployed from the same code.
+    fn __constructor(
+        env: Env,
+        admin: Address,
+        total_supply: i128,
+        cap: Option<i128>,
+        metadata: TokenMetadata,
+    ) -> Result<(), TokenError>;
     fn name(env: Env) -> String;
     fn symbol(env: Env) -> String;
     fn decimals(env: Env) -> u32;
 
     fn total_supply(env: Env) -> i128;
+    /// The SEP-41 name; `balance_of` is kept for existing callers.
+    fn balance(env: Env, id: Address) -> i128;
     fn balance_of(env: Env, owner: Address) -> i128;
 
     fn allowance(env: Env, owner: Address, spender: Address) -> i128;
-    fn approve(env: Env, from: Address, spender: Address, amount: i128);
+    fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32);
+    /// Registers the ed25519 key whose signatures `permit` accepts for
+    /// `owner`, or removes it with `None`. Only the owner can set it, so it
+    /// goes through the owner's own signers, once, rather than `permit`
+    /// trusting a key the account never agreed to.
+    fn set_permit_key(env: Env, owner: Address, key: Option<BytesN<32>>);
+    fn permit_key(env: Env, owner: Address) -> Option<BytesN<32>>;
+    /// `approve` on behalf of `owner`, authorized by its permit key's
+    /// signature over the permit payload instead of a transaction from the
+    /// owner. `nonce` must be the owner's current `permit_nonce`, and is
+    /// used up by the call.
+    fn permit(
+        env: Env,
+        owner: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+        nonce: u64,
+        signature: BytesN<64>,
+    ) -> Result<(), TokenError>;
+    fn permit_nonce(env: Env, owner: Address) -> u64;
     fn transfer(env: Env, from: Address, to: Address, amount: i128);
     fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128);
 
-    fn mint(env: Env, to: Address, amount: i128);
+    fn mint(env: Env, minter: Address, to: Address, amount: i128);
     fn burn(env: Env, from: Address, amount: i128);
     fn burn_from(env: Env, spender: Address, from: Address, amount: i128);
+
+    /// Lets `minter` mint up to `allowance` more tokens. Only the admin can
+    /// grant or revoke the role; the admin itself can always mint.
+    fn grant_minter(env: Env, minter: Address, allowance: i128);
+    fn revoke_minter(env: Env, minter: Address);
+    fn minter_allowance(env: Env, minter: Address) -> Option<i128>;
+    fn supply_cap(env: Env) -> Option<i128>;
+
+    /// Emergency stop. While paused `transfer`, `transfer_from`, `burn`,
+    /// `burn_from` and `mint` fail with `TokenError::Paused`.
+    fn pause(env: Env) -> Result<(), TokenError>;
+    fn unpause(env: Env) -> Result<(), TokenError>;
+    fn is_paused(env: Env) -> bool;
+
+    /// A frozen account can neither send nor receive tokens, nor spend an
+    /// allowance or mint, until the admin unfreezes it.
+    fn freeze(env: Env, account: Address);
+    fn unfreeze(env: Env, account: Address);
+    fn is_frozen(env: Env, account: Address) -> bool;
+    /// Lets the admin burn `amount` from any account, frozen or not.
+    fn clawback(env: Env, from: Address, amount: i128);
+
+    /// Balance and supply as of the end of `ledger`. Only past ledgers can
+    /// be queried, since the current one may still change.
+    fn balance_at(env: Env, id: Address, ledger: u32) -> Result<i128, TokenError>;
+    fn total_supply_at(env: Env, ledger: u32) -> Result<i128, TokenError>;
+
+    /// Gives the voting power of `delegator`'s balance to `delegatee`.
+    /// Balances only count as votes once delegated, to oneself or others.
+    fn delegate(env: Env, delegator: Address, delegatee: Address);
+    fn delegates(env: Env, delegator: Address) -> Option<Address>;
+    fn votes_of(env: Env, account: Address) -> i128;
+    fn past_votes(env: Env, account: Address, ledger: u32) -> Result<i128, TokenError>;
+
+    /// Takes `fee_bps` basis points of every `transfer` and `transfer_from`
+    /// out of the amount received and sends it to `treasury`, which must
+    /// not be frozen. At most `MAX_FEE_BPS`; 0 turns the fee off.
+    fn set_fee(env: Env, fee_bps: u32, treasury: Address) -> Result<(), TokenError>;
+    fn fee(env: Env) -> Option<FeeConfig>;
+    /// No fee is charged when either side of a transfer, or the spender of
+    /// a `transfer_from`, is exempt.
+    fn set_fee_exempt(env: Env, account: Address, exempt: bool);
+    fn is_fee_exempt(env: Env, account: Address) -> bool;
 }
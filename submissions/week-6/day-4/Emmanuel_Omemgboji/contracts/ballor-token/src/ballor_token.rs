@@ -1,8 +1,11 @@
-use soroban_sdk::{contract, contractimpl, Address, Env, String};
+use soroban_sdk::{contract, contractimpl, panic_with_error, symbol_short, Address, Env, String};
 
-use crate::state::{AllowanceKey, AllowanceValue, DataKey};
+use crate::state::{AllowanceKey, AllowanceValue, DataKey, FeeConfig, TokenError};
 use crate::traits::TokenInterface;
 
+// Transfer fees are capped at 10%
+pub const MAX_FEE_BPS: u32 = 1_000;
+
 #[contract]
 pub struct TokenContract;
 
@@ -53,30 +56,78 @@ impl TokenContract {
     }
 
     pub fn get_total_supply(env: Env) -> i128 {
-        let total_supply = env.storage().instance().get(&DataKey::TotalSupply).unwrap();
-        total_supply
+        env.storage().instance().get(&DataKey::TotalSupply).unwrap()
+    }
+
+    // `fee_bps` of every transfer goes to `treasury`, 0 turns it off
+    pub fn set_fee(env: Env, fee_bps: u32, treasury: Address) {
+        let admin = env
+            .storage()
+            .instance()
+            .get::<DataKey, Address>(&DataKey::Admin)
+            .unwrap();
+
+        admin.require_auth();
+
+        if fee_bps > MAX_FEE_BPS {
+            panic!("Fee too high");
+        }
+
+        let config = FeeConfig {
+            fee_bps,
+            treasury: treasury.clone(),
+        };
+        env.storage().instance().set(&DataKey::FeeConfig, &config);
+
+        env.events()
+            .publish((symbol_short!("fee_set"), admin), (fee_bps, treasury));
+    }
+
+    pub fn fee_config(env: Env) -> Option<FeeConfig> {
+        env.storage().instance().get(&DataKey::FeeConfig)
+    }
+
+    // For system contracts such as payroll, which should not pay fees
+    pub fn set_fee_exempt(env: Env, account: Address, exempt: bool) {
+        let admin = env
+            .storage()
+            .instance()
+            .get::<DataKey, Address>(&DataKey::Admin)
+            .unwrap();
+
+        admin.require_auth();
+
+        let key = DataKey::FeeExempt(account.clone());
+        if exempt {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        env.events()
+            .publish((symbol_short!("exempt"), admin, account), exempt);
+    }
+
+    pub fn is_fee_exempt(env: Env, account: Address) -> bool {
+        env.storage().persistent().has(&DataKey::FeeExempt(account))
     }
 }
 
 #[contractimpl]
 impl TokenInterface for TokenContract {
     fn name(env: Env) -> String {
-        let name = env.storage().instance().get(&DataKey::Name).unwrap();
-        name
+        env.storage().instance().get(&DataKey::Name).unwrap()
     }
     fn symbol(env: Env) -> String {
-        let symbol = env.storage().instance().get(&DataKey::Symbol).unwrap();
-        symbol
+        env.storage().instance().get(&DataKey::Symbol).unwrap()
     }
     fn decimals(env: Env) -> u32 {
-        let decimals = env.storage().instance().get(&DataKey::Decimals).unwrap();
-        decimals
+        env.storage().instance().get(&DataKey::Decimals).unwrap()
     }
 
     fn balance(env: Env, id: Address) -> i128 {
         let key = DataKey::Balance(id);
-        let balance = env.storage().persistent().get(&key).unwrap_or(0);
-        balance
+        env.storage().persistent().get(&key).unwrap_or(0)
     }
     fn transfer(env: Env, from: Address, to: Address, amount: i128) {
         from.require_auth();
@@ -85,23 +136,7 @@ impl TokenInterface for TokenContract {
             panic!("Amount must be greater than zero");
         }
 
-        let from_key = DataKey::Balance(from.clone());
-        let from_balance = Self::balance(env.clone(), from);
-
-        if from_balance < amount {
-            panic!("Insufficient balance");
-        }
-
-        env.storage()
-            .persistent()
-            .set(&from_key, &(from_balance - amount));
-
-        let to_key = DataKey::Balance(to.clone());
-        let to_balance = Self::balance(env.clone(), to);
-
-        env.storage()
-            .persistent()
-            .set(&to_key, &(to_balance + amount));
+        Self::move_balance(&env, None, from, to, amount);
     }
 
     fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
@@ -147,7 +182,7 @@ impl TokenInterface for TokenContract {
             spender: spender.clone(),
         });
 
-        let allowance = Self::allowance(env.clone(), from.clone(), spender);
+        let allowance = Self::allowance(env.clone(), from.clone(), spender.clone());
 
         if amount > allowance {
             panic!("Insufficient allowance");
@@ -163,7 +198,7 @@ impl TokenInterface for TokenContract {
             .persistent()
             .set(&allowance_key, &new_allowance);
 
-        Self::transfer(env, from, to, amount);
+        Self::move_balance(&env, Some(spender), from, to, amount);
     }
 
     fn burn(env: Env, from: Address, amount: i128) {
@@ -231,3 +266,53 @@ impl TokenInterface for TokenContract {
             .set(&DataKey::TotalSupply, &new_total_supply);
     }
 }
+
+impl TokenContract {
+    // Moves `amount` from `from`, sending the fee to the treasury unless
+    // either side, or the spender moving it, is exempt. Callers check the
+    // authorization.
+    fn move_balance(env: &Env, spender: Option<Address>, from: Address, to: Address, amount: i128) {
+        let from_key = DataKey::Balance(from.clone());
+        let from_balance = Self::balance(env.clone(), from.clone());
+
+        if from_balance < amount {
+            panic!("Insufficient balance");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&from_key, &(from_balance - amount));
+
+        let mut fee = 0;
+        if let Some(config) = Self::fee_config(env.clone()) {
+            let exempt = Self::is_fee_exempt(env.clone(), from.clone())
+                || Self::is_fee_exempt(env.clone(), to.clone())
+                || spender.is_some_and(|spender| Self::is_fee_exempt(env.clone(), spender));
+            if !exempt {
+                fee = amount
+                    .checked_mul(config.fee_bps as i128)
+                    .unwrap_or_else(|| panic_with_error!(env, TokenError::Overflow))
+                    / 10_000;
+            }
+
+            if fee > 0 {
+                let treasury_key = DataKey::Balance(config.treasury.clone());
+                let treasury_balance = Self::balance(env.clone(), config.treasury.clone());
+
+                env.storage()
+                    .persistent()
+                    .set(&treasury_key, &(treasury_balance + fee));
+
+                env.events()
+                    .publish((symbol_short!("fee"), from, config.treasury), fee);
+            }
+        }
+
+        let to_key = DataKey::Balance(to.clone());
+        let to_balance = Self::balance(env.clone(), to);
+
+        env.storage()
+            .persistent()
+            .set(&to_key, &(to_balance + amount - fee));
+    }
+}
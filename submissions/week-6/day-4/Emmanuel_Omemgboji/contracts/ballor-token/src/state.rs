@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracterror, contracttype, Address};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -14,6 +14,13 @@ pub struct AllowanceValue {
     pub expiration_ledger: u32,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    pub fee_bps: u32,
+    pub treasury: Address,
+}
+
 #[contracttype]
 pub enum DataKey {
     Allowance(AllowanceKey),
@@ -23,4 +30,13 @@ pub enum DataKey {
     Decimals,
     TotalSupply,
     Admin,
+    FeeConfig,
+    FeeExempt(Address),
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum TokenError {
+    Overflow = 1,
 }
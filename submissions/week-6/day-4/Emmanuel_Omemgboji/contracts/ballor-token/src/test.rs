@@ -1,6 +1,11 @@
 #![cfg(test)]
-use crate::ballor_token::{TokenContract, TokenContractClient};
-use soroban_sdk::{testutils::Address as AddressUtils, Address, Env, String};
+use crate::ballor_token::{TokenContract, TokenContractClient, MAX_FEE_BPS};
+use crate::state::TokenError;
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as AddressUtils, Events, MockAuth, MockAuthInvoke},
+    vec, Address, Env, IntoVal, String,
+};
 
 fn setup(env: &Env, admin: &Address) -> TokenContractClient<'static> {
     let name = String::from_str(&env, &"ballor-token");
@@ -101,3 +106,130 @@ fn test() {
     assert_eq!(user2_balance_after_transfer_from, transfer_from_amount);
     assert_eq!(user4_balance_after_transfer_from, allowed_amount_after_burn);
 }
+
+#[test]
+fn test_transfer_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let payroll = Address::generate(&env);
+    let treasury = Address::generate(&env);
+
+    let ballor_token = setup(&env, &admin);
+    ballor_token.mint(&user1, &10_000);
+
+    // No fee until the admin sets one
+    ballor_token.transfer(&user1, &user2, &1_000);
+    assert_eq!(ballor_token.balance(&user2), 1_000);
+    assert_eq!(ballor_token.fee_config(), None);
+
+    // 1% to the treasury
+    ballor_token.set_fee(&100, &treasury);
+    ballor_token.transfer(&user1, &user2, &1_000);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                ballor_token.address.clone(),
+                (symbol_short!("fee"), user1.clone(), treasury.clone()).into_val(&env),
+                10_i128.into_val(&env)
+            )
+        ]
+    );
+    assert_eq!(ballor_token.balance(&user1), 8_000);
+    assert_eq!(ballor_token.balance(&user2), 1_990);
+    assert_eq!(ballor_token.balance(&treasury), 10);
+
+    ballor_token.approve(&user1, &user2, &2_000, &100);
+    ballor_token.transfer_from(&user2, &user1, &user2, &2_000);
+    assert_eq!(ballor_token.balance(&user2), 3_970);
+    assert_eq!(ballor_token.balance(&treasury), 30);
+
+    // Payroll pays and is paid without fees
+    ballor_token.set_fee_exempt(&payroll, &true);
+    assert!(ballor_token.is_fee_exempt(&payroll));
+    ballor_token.transfer(&user1, &payroll, &1_000);
+    ballor_token.transfer(&payroll, &user2, &500);
+    assert_eq!(ballor_token.balance(&payroll), 500);
+    assert_eq!(ballor_token.balance(&user2), 4_470);
+    assert_eq!(ballor_token.balance(&treasury), 30);
+
+    // So is payroll moving someone else's tokens
+    ballor_token.approve(&user1, &payroll, &500, &100);
+    ballor_token.transfer_from(&payroll, &user1, &user2, &500);
+    assert_eq!(ballor_token.balance(&user1), 4_500);
+    assert_eq!(ballor_token.balance(&user2), 4_970);
+    assert_eq!(ballor_token.balance(&treasury), 30);
+
+    assert_eq!(ballor_token.get_total_supply(), 10_000);
+}
+
+#[test]
+fn test_fee_overflow_is_an_error() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let treasury = Address::generate(&env);
+
+    let ballor_token = setup(&env, &admin);
+    ballor_token.mint(&user1, &i128::MAX);
+    ballor_token.set_fee(&100, &treasury);
+
+    assert_eq!(
+        ballor_token.try_transfer(&user1, &user2, &i128::MAX),
+        Err(Ok(soroban_sdk::Error::from_contract_error(
+            TokenError::Overflow as u32
+        )))
+    );
+    assert_eq!(ballor_token.balance(&user1), i128::MAX);
+}
+
+#[test]
+#[should_panic(expected = "Fee too high")]
+fn test_fee_is_capped() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let ballor_token = setup(&env, &admin);
+
+    ballor_token.set_fee(&(MAX_FEE_BPS + 1), &treasury);
+}
+
+#[test]
+fn test_transfer_from_needs_only_the_spender() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    let ballor_token = setup(&env, &admin);
+    ballor_token.mint(&owner, &1_000);
+    ballor_token.approve(&owner, &spender, &500, &100);
+
+    // The allowance is the owner's consent, so only the spender signs
+    env.mock_auths(&[MockAuth {
+        address: &spender,
+        invoke: &MockAuthInvoke {
+            contract: &ballor_token.address,
+            fn_name: "transfer_from",
+            args: (spender.clone(), owner.clone(), spender.clone(), 500_i128).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    ballor_token.transfer_from(&spender, &owner, &spender, &500);
+
+    assert_eq!(ballor_token.balance(&owner), 500);
+    assert_eq!(ballor_token.balance(&spender), 500);
+    assert_eq!(ballor_token.allowance(&owner, &spender), 0);
+}